@@ -5,6 +5,7 @@ use std::io::Write;
 pub enum PaintStyle {
     Fill = 0,
     Stroke = 1,
+    StrokeAndFill = 2,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -49,6 +50,7 @@ pub enum BlendMode {
     Screen = 10,
     Darken = 11,
     Lighten = 12,
+    Plus = 13,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -59,6 +61,40 @@ pub enum FilterQuality {
     High = 3,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    Png = 0,
+    Jpeg = 1,
+    Webp = 2,
+}
+
+/// A surface pixels layout.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ColorType {
+    Rgba8888 = 0,
+    Bgra8888 = 1,
+    /// Any other layout, which is not used by resvg.
+    Other = 2,
+}
+
+/// A fill and stroke drawing order.
+///
+/// Matches the SVG `paint-order` property.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PaintOrder {
+    FillFirst = 0,
+    StrokeFirst = 1,
+}
+
+/// Stroke parameters that a path effect is applied with.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StrokeParams {
+    pub width: f64,
+    pub cap: StrokeCap,
+    pub join: StrokeJoin,
+    pub miter_limit: f64,
+}
+
 pub struct Surface {
     surface: skia_safe::Surface,
     canvas: Canvas,
@@ -66,13 +102,234 @@ pub struct Surface {
 
 impl Surface {
     pub fn new_rgba(width: u32, height: u32) -> Option<Surface> {
-        Surface::new_rgba_impl(width, height, skia_safe::AlphaType::Unpremul)
+        Surface::new_rgba_impl(width, height, skia_safe::AlphaType::Unpremul, None)
     }
 
     pub fn new_rgba_premultiplied(width: u32, height: u32) -> Option<Surface> {
-        Surface::new_rgba_impl(width, height, skia_safe::AlphaType::Premul)
+        Surface::new_rgba_impl(width, height, skia_safe::AlphaType::Premul, None)
+    }
+
+    pub fn new_rgba_premultiplied_linear(width: u32, height: u32) -> Option<Surface> {
+        let color_space = skia_safe::ColorSpace::new_srgb_linear();
+        Surface::new_rgba_impl(width, height, skia_safe::AlphaType::Premul, Some(color_space))
+    }
+
+    pub fn copy_to_srgb(&self) -> Option<Surface> {
+        let color_space = skia_safe::ColorSpace::new_srgb();
+        let mut copy = Surface::new_rgba_impl(
+            self.width(), self.height(), skia_safe::AlphaType::Premul, Some(color_space),
+        )?;
+        self.surface.clone().draw(copy.surface.canvas(), (0.0, 0.0), None);
+        Some(copy)
+    }
+
+    /// Decodes a PNG, JPEG or WebP image into a new premultiplied surface.
+    ///
+    /// Returns `None` when the data is not recognized or is corrupted.
+    pub fn from_encoded(bytes: &[u8]) -> Option<Surface> {
+        let image = skia_safe::Image::from_encoded(skia_safe::Data::new_copy(bytes))?;
+        // Images are decoded lazily, so force decoding to catch corrupted data.
+        let image = image.new_raster_image()?;
+
+        let mut surface = Surface::new_rgba_premultiplied(image.width() as u32, image.height() as u32)?;
+        surface.surface.canvas().draw_image(&image, (0.0, 0.0), None);
+        Some(surface)
     }
     
+    /// Creates a GPU-backed surface.
+    ///
+    /// Falls back to a raster surface when `context` is `None`.
+    ///
+    /// Pixels of a GPU-backed surface are not directly accessible,
+    /// so `data()` must not be used. Use `read_pixels()`, which will perform a readback.
+    #[cfg(feature = "gpu")]
+    pub fn new_gpu(
+        context: Option<&mut skia_safe::gpu::DirectContext>,
+        width: u32,
+        height: u32,
+    ) -> Option<Surface> {
+        let context = match context {
+            Some(context) => context,
+            None => return Surface::new_rgba_premultiplied(width, height),
+        };
+
+        let size = skia_safe::ISize::new(width as i32, height as i32);
+        let image_info = skia_safe::ImageInfo::new(
+            size, skia_safe::ColorType::n32(), skia_safe::AlphaType::Premul, None,
+        );
+        let surface = skia_safe::Surface::new_render_target(
+            context, skia_safe::Budgeted::Yes, &image_info, None, None, None, None,
+        )?;
+        let canvas = Canvas(surface.clone());
+        Some(Surface { surface, canvas })
+    }
+
+    /// Flushes pending drawing commands and submits them to the GPU.
+    ///
+    /// When `sync` is set, waits for the GPU to finish.
+    /// Must be called before reading pixels from a GPU-backed surface.
+    ///
+    /// Acts like `Canvas::flush` for raster surfaces.
+    pub fn flush_and_submit(&mut self, sync: bool) {
+        #[cfg(feature = "gpu")]
+        {
+            if let Some(mut context) = self.surface.direct_context() {
+                self.surface.flush();
+                context.submit(Some(sync));
+                return;
+            }
+        }
+
+        let _ = sync;
+        self.canvas.flush();
+    }
+
+    /// Blurs the surface content in place.
+    ///
+    /// Does nothing when `sigma` is not a positive number.
+    ///
+    /// Allocates a surface snapshot on each call,
+    /// so an image filter should be preferred for repeated blurs.
+    pub fn blur(&mut self, sigma: f64) {
+        if !sigma.is_finite() || sigma <= 0.0 {
+            return;
+        }
+
+        let sigma = sigma as f32;
+        let filter = match skia_safe::image_filters::blur((sigma, sigma), None, None, None) {
+            Some(filter) => filter,
+            None => return,
+        };
+
+        let image = self.image_snapshot();
+        let mut paint = skia_safe::Paint::default();
+        paint.set_image_filter(Some(filter));
+        paint.set_blend_mode(skia_safe::BlendMode::Src);
+
+        let canvas = self.surface.canvas();
+        canvas.save();
+        canvas.reset_matrix();
+        canvas.clear(skia_safe::Color::TRANSPARENT);
+        canvas.draw_image(&image, (0.0, 0.0), Some(&paint));
+        canvas.restore();
+    }
+
+    /// Multiplies all surface pixels by `alpha / 255`.
+    ///
+    /// Cheaper than drawing the surface with an alpha paint when the result
+    /// should stay in the same surface. Transparent pixels stay transparent.
+    /// The current matrix is ignored, but the current clip is not.
+    pub fn apply_opacity(&mut self, alpha: u8) {
+        if alpha == 255 {
+            return;
+        }
+
+        let mut paint = skia_safe::Paint::default();
+        paint.set_color(skia_safe::Color::from_argb(alpha, 0, 0, 0));
+        paint.set_blend_mode(skia_safe::BlendMode::DstIn);
+
+        let canvas = self.surface.canvas();
+        canvas.save();
+        canvas.reset_matrix();
+        canvas.draw_paint(&paint);
+        canvas.restore();
+    }
+
+    /// Copies surface pixels.
+    ///
+    /// Unlike `data()`, works with GPU-backed surfaces too, but will trigger a readback.
+    pub fn read_pixels(&self) -> Option<Vec<u8>> {
+        let mut surface = self.surface.clone();
+        let image_info = surface.image_info();
+        let row_bytes = image_info.min_row_bytes();
+        let mut data = vec![0; row_bytes * self.height() as usize];
+        if surface.read_pixels(&image_info, &mut data, row_bytes, (0, 0)) {
+            Some(data)
+        } else {
+            None
+        }
+    }
+
+    /// Creates a surface that draws directly into `data`.
+    ///
+    /// `data` must contain premultiplied RGBA or BGRA pixels, depending on `is_bgra()`,
+    /// with `stride` bytes per row.
+    ///
+    /// Returns `None` when the size is zero, the stride is smaller than a row
+    /// or the buffer is too small.
+    ///
+    /// The returned surface borrows `data` mutably for its whole lifetime,
+    /// so the buffer cannot be read or freed while the surface is alive.
+    /// It provides only a shared access to the underlying `Surface` and `Canvas`,
+    /// while drawing is done through its own methods. Otherwise, the canvas could
+    /// be swapped into another surface and outlive the buffer.
+    ///
+    /// ```compile_fail
+    /// let mut data = vec![0; 16];
+    /// let mut surface = resvg_skia::Surface::wrap_pixels(2, 2, 8, &mut data).unwrap();
+    /// data[0] = 255;
+    /// surface.fill(0, 0, 0, 255);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// let mut data = vec![0; 16];
+    /// let mut other = resvg_skia::Surface::new_rgba_premultiplied(2, 2).unwrap();
+    /// let mut surface = resvg_skia::Surface::wrap_pixels(2, 2, 8, &mut data).unwrap();
+    /// std::mem::swap::<resvg_skia::Canvas>(&mut surface, &mut other);
+    /// ```
+    pub fn wrap_pixels<'a>(
+        width: u32,
+        height: u32,
+        stride: usize,
+        data: &'a mut [u8],
+    ) -> Option<BorrowedSurface<'a>> {
+        // The borrow is tracked by `BorrowedSurface` instead.
+        let surface = unsafe { Surface::wrap_pixels_unchecked(width, height, stride, data, false)? };
+        Some(BorrowedSurface {
+            surface,
+            _data: std::marker::PhantomData,
+        })
+    }
+
+    /// Like `wrap_pixels`, but the buffer borrow is not tracked.
+    ///
+    /// When `linear` is set, the surface will have a linear RGB color space,
+    /// like the one created by `new_rgba_premultiplied_linear`.
+    ///
+    /// # Safety
+    ///
+    /// `data` must outlive the returned surface and must not be accessed
+    /// or moved while the surface is alive.
+    pub unsafe fn wrap_pixels_unchecked(
+        width: u32,
+        height: u32,
+        stride: usize,
+        data: &mut [u8],
+        linear: bool,
+    ) -> Option<Surface> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        if stride < (width as usize).checked_mul(4)? {
+            return None;
+        }
+
+        if data.len() < stride.checked_mul(height as usize)? {
+            return None;
+        }
+
+        let color_space = if linear { Some(skia_safe::ColorSpace::new_srgb_linear()) } else { None };
+        let size = skia_safe::ISize::new(width as i32, height as i32);
+        let image_info = skia_safe::ImageInfo::new(
+            size, skia_safe::ColorType::n32(), skia_safe::AlphaType::Premul, color_space,
+        );
+        let surface = skia_safe::Surface::new_raster_direct(&image_info, data, stride, None)?;
+        let surface = surface.release();
+        let canvas = Canvas(surface.clone());
+        Some(Surface { surface, canvas })
+    }
+
     pub fn from_skia_safe_canvas(canvas: &mut skia_safe::Canvas) -> Option<Surface> {
         let surface = unsafe { canvas.surface() }?;
         let canvas = Canvas(surface.clone());
@@ -83,18 +340,57 @@ impl Surface {
         unimplemented!();
     }
 
+    /// Copies a region into a new unpremultiplied surface.
+    ///
+    /// A copy of a surface with a color space, like the one created by
+    /// `new_rgba_premultiplied_linear`, is converted to sRGB.
     pub fn copy_rgba(&self, x: u32, y: u32, width: u32, height: u32) -> Option<Surface> {
-        let mut copy = Surface::new_rgba(width, height)?;
+        let color_space = self.surface.clone().image_info().color_space()
+            .map(|_| skia_safe::ColorSpace::new_srgb());
+        let mut copy = Surface::new_rgba_impl(
+            width, height, skia_safe::AlphaType::Unpremul, color_space,
+        )?;
         let mut paint = skia_safe::Paint::default();
         paint.set_filter_quality(skia_safe::FilterQuality::Low);
         self.surface.clone().draw(copy.surface.canvas(), (-(x as f32), -(y as f32)), Some(&paint));
         Some(copy)
     }
 
+    /// Returns a copy of the surface scaled to the specified size.
+    ///
+    /// `FilterQuality::High` and `FilterQuality::Medium` use mipmaps when downscaling,
+    /// so large scale factors do not alias. `Low` uses a plain bilinear filter
+    /// and `None` picks the nearest pixel.
+    ///
+    /// Returns `None` when the size is zero or on allocation failure.
+    pub fn resize(&self, width: u32, height: u32, filter_quality: FilterQuality) -> Option<Surface> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let info = self.surface.clone().image_info();
+        let mut resized = Surface::new_rgba_impl(
+            width, height, info.alpha_type(), info.color_space(),
+        )?;
+        resized.draw_surface_rect(self, 0.0, 0.0, width as f64, height as f64, filter_quality);
+        Some(resized)
+    }
+
     pub fn try_clone(&self) -> Option<Surface> {
         self.copy_rgba(0, 0, self.width(), self.height())
     }
 
+    /// Returns an immutable snapshot of the surface content.
+    ///
+    /// The snapshot is copy-on-write: no pixels are copied until the surface
+    /// is drawn to while the snapshot is still alive. Drawing the same snapshot
+    /// multiple times is cheaper than `draw_surface`, which makes a new snapshot on each call.
+    ///
+    /// Later changes to the surface are not visible in the snapshot.
+    pub fn snapshot(&self) -> SurfaceImage {
+        SurfaceImage(self.image_snapshot())
+    }
+
     pub fn save_png(&self, path: &str) -> bool {
         let mut bytes: Vec<u8> = vec![];
         {
@@ -114,6 +410,44 @@ impl Surface {
         return true;
     }
 
+    /// Encodes the surface content.
+    ///
+    /// `quality` is in a 0..100 range and is ignored for PNG.
+    pub fn encode(&self, format: ImageFormat, quality: u8) -> Option<Vec<u8>> {
+        let quality = std::cmp::min(quality, 100) as i32;
+        let data = self.image_snapshot().encode_to_data_with_quality(format.to_skia(), quality)?;
+        Some(data.as_bytes().to_vec())
+    }
+
+    /// Checks that the current Skia build can encode images in the specified format.
+    ///
+    /// JPEG and WebP encoders are optional Skia build features.
+    /// The check is done by encoding a 1x1 image once. The result is cached.
+    pub fn supports_format(format: ImageFormat) -> bool {
+        use std::sync::atomic::{AtomicU8, Ordering};
+
+        const UNKNOWN: u8 = 0;
+        const UNSUPPORTED: u8 = 1;
+        const SUPPORTED: u8 = 2;
+
+        static CACHE: [AtomicU8; 3] = [
+            AtomicU8::new(UNKNOWN), AtomicU8::new(UNKNOWN), AtomicU8::new(UNKNOWN),
+        ];
+
+        let cached = &CACHE[format as usize];
+        match cached.load(Ordering::Relaxed) {
+            UNSUPPORTED => return false,
+            SUPPORTED => return true,
+            _ => {}
+        }
+
+        let supported = Surface::new_rgba_premultiplied(1, 1)
+            .and_then(|surface| surface.encode(format, 100))
+            .is_some();
+        cached.store(if supported { SUPPORTED } else { UNSUPPORTED }, Ordering::Relaxed);
+        supported
+    }
+
     pub fn width(&self) -> u32 {
         self.surface.width() as u32
     }
@@ -122,6 +456,9 @@ impl Surface {
         self.surface.height() as u32
     }
 
+    /// Returns surface pixels.
+    ///
+    /// Panics on GPU-backed surfaces. Use `read_pixels()` instead.
     pub fn data(&self) -> SurfaceData {
         unsafe {
             let mut surface = self.surface.clone();
@@ -136,6 +473,17 @@ impl Surface {
         self.data()
     }
 
+    /// Returns the pixels layout of this surface.
+    ///
+    /// Unlike `is_bgra()`, also works for wrapped and GPU-backed surfaces.
+    pub fn color_type(&self) -> ColorType {
+        match self.surface.image_info().color_type() {
+            skia_safe::ColorType::RGBA8888 => ColorType::Rgba8888,
+            skia_safe::ColorType::BGRA8888 => ColorType::Bgra8888,
+            _ => ColorType::Other,
+        }
+    }
+
     pub fn is_bgra() -> bool {
         skia_safe::ColorType::n32() == skia_safe::ColorType::BGRA8888
     }
@@ -160,6 +508,95 @@ impl Drop for Surface {
     }
 }
 
+/// A surface that draws into an external buffer.
+///
+/// See `Surface::wrap_pixels`.
+pub struct BorrowedSurface<'a> {
+    surface: Surface,
+    _data: std::marker::PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> Deref for BorrowedSurface<'a> {
+    type Target = Surface;
+
+    fn deref(&self) -> &Surface {
+        &self.surface
+    }
+}
+
+macro_rules! forward_to_surface {
+    ($($(#[$meta:meta])* fn $name:ident(&mut self $(, $arg:ident: $ty:ty)*) $(-> $ret:ty)?;)*) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(&mut self $(, $arg: $ty)*) $(-> $ret)? {
+                self.surface.$name($($arg),*)
+            }
+        )*
+    };
+}
+
+/// Mutable `Surface` and `Canvas` methods.
+impl<'a> BorrowedSurface<'a> {
+    forward_to_surface! {
+        fn blur(&mut self, sigma: f64);
+        fn apply_opacity(&mut self, alpha: u8);
+        fn data_mut(&mut self) -> SurfaceData;
+        fn clear(&mut self);
+        fn fill(&mut self, r: u8, g: u8, b: u8, a: u8);
+        #[allow(clippy::too_many_arguments)]
+        fn clear_rect(&mut self, x: u32, y: u32, w: u32, h: u32, r: u8, g: u8, b: u8, a: u8);
+        fn flush(&mut self);
+        fn set_matrix(&mut self, matrix: &Matrix);
+        fn concat(&mut self, matrix: &Matrix);
+        fn scale(&mut self, sx: f64, sy: f64);
+        fn translate(&mut self, dx: f64, dy: f64);
+        fn rotate(&mut self, degrees: f64);
+        fn skew(&mut self, sx: f64, sy: f64);
+        fn draw_path(&mut self, path: &Path, paint: &Paint);
+        fn draw_path_fill_stroke(&mut self, path: &Path, fill: &Paint, stroke: &Paint,
+                                 order: PaintOrder);
+        fn draw_path_clipped(&mut self, path: &Path, paint: &Paint, clip: &Path);
+        fn draw_rect(&mut self, x: f64, y: f64, w: f64, h: f64, paint: &Paint);
+        fn draw_rect_tiled(&mut self, dst: (f64, f64, f64, f64), tile: &Surface, matrix: Matrix,
+                           tile_x: TileMode, tile_y: TileMode);
+        #[allow(clippy::too_many_arguments)]
+        fn fill_rect(&mut self, x: f64, y: f64, w: f64, h: f64, r: u8, g: u8, b: u8, a: u8);
+        fn fill_path(&mut self, path: &Path, r: u8, g: u8, b: u8, a: u8);
+        fn draw_surface(&mut self, surface: &Surface, left: f64, top: f64, alpha: u8,
+                        blend_mode: BlendMode, filter_quality: FilterQuality);
+        fn draw_image_handle(&mut self, image: &SurfaceImage, left: f64, top: f64, alpha: u8,
+                             blend_mode: BlendMode, filter_quality: FilterQuality);
+        fn draw_surface_rect(&mut self, surface: &Surface, x: f64, y: f64, w: f64, h: f64,
+                             filter_quality: FilterQuality);
+        fn draw_surface_src_rect(&mut self, surface: &Surface, src: (f64, f64, f64, f64),
+                                 dst: (f64, f64, f64, f64), filter_quality: FilterQuality);
+        fn draw_image_nine(&mut self, surface: &Surface, center: (f64, f64, f64, f64),
+                           dst: (f64, f64, f64, f64), filter_quality: FilterQuality) -> bool;
+        fn reset_matrix(&mut self);
+        fn set_clip_rect(&mut self, x: f64, y: f64, w: f64, h: f64);
+        fn save(&mut self);
+        fn save_layer_bounds(&mut self, bounds: (f64, f64, f64, f64), alpha: u8);
+        fn restore(&mut self);
+        fn draw_str(&mut self, text: &str, x: f64, y: f64, font: &Font, paint: &Paint);
+    }
+}
+
+/// An immutable snapshot of a surface.
+///
+/// See `Surface::snapshot`.
+#[derive(Clone)]
+pub struct SurfaceImage(skia_safe::Image);
+
+impl SurfaceImage {
+    pub fn width(&self) -> u32 {
+        self.0.width() as u32
+    }
+
+    pub fn height(&self) -> u32 {
+        self.0.height() as u32
+    }
+}
+
 pub struct SurfaceData<'a> {
     slice: &'a mut [u8],
 }
@@ -185,9 +622,23 @@ impl Color {
         Color(a, r, g, b)
     }
 
+    /// Creates a new color from `usvg` color and opacity.
+    ///
+    /// Opacity is clamped to a 0..1 range and stored as an unpremultiplied alpha.
+    /// The alpha is truncated and not rounded, so 0.5 becomes 127.
+    #[cfg(feature = "usvg")]
+    pub fn from_usvg(color: usvg::Color, opacity: f64) -> Color {
+        let a = (opacity.max(0.0).min(1.0) * 255.0) as u8;
+        Color(a, color.red, color.green, color.blue)
+    }
+
     pub fn to_u32(&self) -> u32 {
         (self.0 as u32) << 24 | (self.1 as u32) << 16 | (self.2 as u32) << 8 | (self.3 as u32)
     }
+
+    pub fn to_skia_color(&self) -> skia_safe::Color {
+        skia_safe::Color::from_argb(self.0, self.1, self.2, self.3)
+    }
 }
 
 
@@ -220,6 +671,28 @@ impl Matrix {
         let data = self.0.to_affine().unwrap();
         (data[0] as f64, data[1] as f64, data[2] as f64, data[3] as f64, data[4] as f64, data[5] as f64)
     }
+
+    /// Returns the matrix determinant.
+    pub fn determinant(&self) -> f64 {
+        let (a, b, c, d, _, _) = self.data();
+        a * d - b * c
+    }
+
+    /// Returns a uniform scale factor.
+    ///
+    /// This is a geometric mean of the x and y scale factors of the decomposed matrix,
+    /// which is equal to the square root of the absolute determinant.
+    /// Translation, rotation and skew do not affect it.
+    ///
+    /// Returns 0.0 for a degenerate matrix.
+    pub fn scale_factor(&self) -> f64 {
+        let det = self.determinant().abs();
+        if det.is_finite() && det > f64::EPSILON {
+            det.sqrt()
+        } else {
+            0.0
+        }
+    }
 }
 
 impl Default for Matrix {
@@ -246,6 +719,24 @@ impl Canvas {
         self.0.canvas().clear(color);
     }
 
+    /// Replaces pixels in a rect with the specified color.
+    ///
+    /// Unlike `fill_rect`, pixels are overwritten and not blended, so a transparent color
+    /// clears the rect. Like `fill`, the rect is in pixels and the current matrix is ignored,
+    /// but the current clip is not.
+    #[allow(clippy::too_many_arguments)]
+    pub fn clear_rect(&mut self, x: u32, y: u32, w: u32, h: u32, r: u8, g: u8, b: u8, a: u8) {
+        let mut paint = skia_safe::Paint::default();
+        paint.set_argb(a, r, g, b);
+        paint.set_blend_mode(skia_safe::BlendMode::Src);
+
+        let canvas = self.0.canvas();
+        canvas.save();
+        canvas.reset_matrix();
+        canvas.draw_rect(skia_safe::Rect::from_xywh(x as f32, y as f32, w as f32, h as f32), &paint);
+        canvas.restore();
+    }
+
     pub fn flush(&mut self) {
         self.0.canvas().flush();
     }
@@ -266,6 +757,15 @@ impl Canvas {
         self.0.canvas().translate((dx as f32, dy as f32));
     }
 
+    /// Rotates around the current origin.
+    pub fn rotate(&mut self, degrees: f64) {
+        self.0.canvas().rotate(degrees as f32, None);
+    }
+
+    pub fn skew(&mut self, sx: f64, sy: f64) {
+        self.0.canvas().skew((sx as f32, sy as f32));
+    }
+
     pub fn get_matrix(&self) -> Matrix {
         let mut surface = self.0.clone();
         Matrix(surface.canvas().total_matrix())
@@ -275,10 +775,65 @@ impl Canvas {
         self.0.canvas().draw_path(&path.0, &paint.0);
     }
 
+    /// Fills and strokes a path in the specified order.
+    pub fn draw_path_fill_stroke(&mut self, path: &Path, fill: &Paint, stroke: &Paint,
+                                 order: PaintOrder) {
+        match order {
+            PaintOrder::FillFirst => {
+                self.draw_path(path, fill);
+                self.draw_path(path, stroke);
+            }
+            PaintOrder::StrokeFirst => {
+                self.draw_path(path, stroke);
+                self.draw_path(path, fill);
+            }
+        }
+    }
+
+    /// Draws a path clipped by another path.
+    ///
+    /// The canvas clip is restored afterwards. `clip` uses its own fill type
+    /// and is antialiased when `paint` is.
+    pub fn draw_path_clipped(&mut self, path: &Path, paint: &Paint, clip: &Path) {
+        let canvas = self.0.canvas();
+        canvas.save();
+        canvas.clip_path(&clip.0, skia_safe::ClipOp::Intersect, paint.is_anti_alias());
+        canvas.draw_path(&path.0, &paint.0);
+        canvas.restore();
+    }
+
     pub fn draw_rect(&mut self, x: f64, y: f64, w: f64, h: f64, paint: &Paint) {
         self.0.canvas().draw_rect(skia_safe::Rect::from_xywh(x as f32, y as f32, w as f32, h as f32), &paint.0);
     }
 
+    /// Fills a `dst` rect, which is `(x, y, width, height)`, with a tiled surface.
+    ///
+    /// `matrix` maps the tile into the current canvas coordinates,
+    /// so the tiles grid starts at its translation and not at the `dst` origin.
+    pub fn draw_rect_tiled(&mut self, dst: (f64, f64, f64, f64), tile: &Surface, matrix: Matrix,
+                           tile_x: TileMode, tile_y: TileMode) {
+        let mut paint = Paint::new();
+        paint.set_shader(&Shader::new_from_surface_image_tiled(tile, matrix, tile_x, tile_y));
+        self.draw_rect(dst.0, dst.1, dst.2, dst.3, &paint);
+    }
+
+    /// Fills a rect with a solid color using antialiasing.
+    ///
+    /// A shorthand for `draw_rect` with a simple fill `Paint`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn fill_rect(&mut self, x: f64, y: f64, w: f64, h: f64, r: u8, g: u8, b: u8, a: u8) {
+        let paint = solid_fill_paint(r, g, b, a);
+        self.0.canvas().draw_rect(skia_safe::Rect::from_xywh(x as f32, y as f32, w as f32, h as f32), &paint);
+    }
+
+    /// Fills a path with a solid color using antialiasing.
+    ///
+    /// A shorthand for `draw_path` with a simple fill `Paint`.
+    pub fn fill_path(&mut self, path: &Path, r: u8, g: u8, b: u8, a: u8) {
+        let paint = solid_fill_paint(r, g, b, a);
+        self.0.canvas().draw_path(&path.0, &paint);
+    }
+
     pub fn draw_surface(&mut self, surface: &Surface, left: f64, top: f64, alpha: u8,
                         blend_mode: BlendMode, filter_quality: FilterQuality) {
         let mut paint = skia_safe::Paint::default();
@@ -288,6 +843,16 @@ impl Canvas {
         self.0.canvas().draw_image(&surface.image_snapshot(), (left as f32, top as f32), Some(&paint));
     }
 
+    /// Like `draw_surface`, but draws an existing snapshot.
+    pub fn draw_image_handle(&mut self, image: &SurfaceImage, left: f64, top: f64, alpha: u8,
+                             blend_mode: BlendMode, filter_quality: FilterQuality) {
+        let mut paint = skia_safe::Paint::default();
+        paint.set_filter_quality(filter_quality.to_skia());
+        paint.set_alpha(alpha);
+        paint.set_blend_mode(blend_mode.to_skia());
+        self.0.canvas().draw_image(&image.0, (left as f32, top as f32), Some(&paint));
+    }
+
     pub fn draw_surface_rect(&mut self, surface: &Surface, x: f64, y: f64, w: f64, h: f64,
                              filter_quality: FilterQuality) {
         let mut paint = skia_safe::Paint::default();
@@ -296,6 +861,55 @@ impl Canvas {
         self.0.canvas().draw_image_rect(&surface.image_snapshot(), None, dst, &paint);
     }
 
+    /// Draws a `src` region of a surface into a `dst` region.
+    ///
+    /// Both rects are `(x, y, width, height)`. `src` is in the source surface pixels
+    /// and `dst` is in the current canvas coordinates, i.e. affected by the current transform.
+    ///
+    /// Pixels outside `src` are never sampled, even with filtering.
+    pub fn draw_surface_src_rect(&mut self, surface: &Surface, src: (f64, f64, f64, f64),
+                                 dst: (f64, f64, f64, f64), filter_quality: FilterQuality) {
+        let mut paint = skia_safe::Paint::default();
+        paint.set_filter_quality(filter_quality.to_skia());
+        let src = skia_safe::Rect::from_xywh(src.0 as f32, src.1 as f32, src.2 as f32, src.3 as f32);
+        let dst = skia_safe::Rect::from_xywh(dst.0 as f32, dst.1 as f32, dst.2 as f32, dst.3 as f32);
+        self.0.canvas().draw_image_rect(
+            &surface.image_snapshot(),
+            Some((&src, skia_safe::canvas::SrcRectConstraint::Strict)),
+            dst,
+            &paint,
+        );
+    }
+
+    /// Draws a surface as a nine-patch.
+    ///
+    /// `center` is an `(x, y, width, height)` region of the source surface that splits it
+    /// into a 3x3 grid. The four corners are drawn unscaled, the edges are stretched
+    /// in one direction and the center is stretched in both to fill `dst`.
+    /// When `dst` is smaller than the corners, they are scaled down proportionally.
+    ///
+    /// `center` coordinates are rounded to whole pixels.
+    ///
+    /// Returns `false` and draws nothing when `center` is empty
+    /// or doesn't lie within the source surface bounds.
+    pub fn draw_image_nine(&mut self, surface: &Surface, center: (f64, f64, f64, f64),
+                           dst: (f64, f64, f64, f64), filter_quality: FilterQuality) -> bool {
+        let center = skia_safe::IRect::from_xywh(
+            center.0.round() as i32, center.1.round() as i32,
+            center.2.round() as i32, center.3.round() as i32,
+        );
+        let bounds = skia_safe::IRect::from_wh(surface.width() as i32, surface.height() as i32);
+        if center.is_empty() || !bounds.contains(&center) {
+            return false;
+        }
+
+        let mut paint = skia_safe::Paint::default();
+        paint.set_filter_quality(filter_quality.to_skia());
+        let dst = skia_safe::Rect::from_xywh(dst.0 as f32, dst.1 as f32, dst.2 as f32, dst.3 as f32);
+        self.0.canvas().draw_image_nine(&surface.image_snapshot(), center, dst, Some(&paint));
+        true
+    }
+
     pub fn reset_matrix(&mut self) {
         self.0.canvas().reset_matrix();
     }
@@ -308,11 +922,45 @@ impl Canvas {
         self.0.canvas().save();
     }
 
+    /// Saves the canvas state and redirects drawing to an offscreen layer.
+    ///
+    /// `bounds` is an `(x, y, width, height)` rect in the current canvas coordinates.
+    /// The layer covers only this rect, so content outside of it will be dropped.
+    /// On `restore` the layer will be composited onto the canvas using `alpha`.
+    pub fn save_layer_bounds(&mut self, bounds: (f64, f64, f64, f64), alpha: u8) {
+        let bounds = skia_safe::Rect::from_xywh(
+            bounds.0 as f32, bounds.1 as f32, bounds.2 as f32, bounds.3 as f32,
+        );
+        self.0.canvas().save_layer_alpha(bounds, alpha as u32);
+    }
+
     pub fn restore(&mut self) {
         self.0.canvas().restore();
     }
+
+    /// Draws a text with a baseline origin at `x`, `y`.
+    pub fn draw_str(&mut self, text: &str, x: f64, y: f64, font: &Font, paint: &Paint) {
+        self.0.canvas().draw_str(text, (x as f32, y as f32), &font.0, &paint.0);
+    }
+
+    /// Returns the current clip bounds in device coordinates as `(x, y, width, height)`.
+    ///
+    /// Returns `None` when the clip is empty, so nothing will be drawn.
+    pub fn clip_bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        let mut surface = self.0.clone();
+        let r = surface.canvas().device_clip_bounds()?;
+        if r.is_empty() {
+            return None;
+        }
+
+        Some((r.left as f64, r.top as f64, r.width() as f64, r.height() as f64))
+    }
 }
 
+/// A paint.
+///
+/// Cloning is cheap, since shaders and path effects are reference counted and shared.
+#[derive(Clone)]
 pub struct Paint(skia_safe::Paint);
 
 impl Paint {
@@ -325,6 +973,9 @@ impl Paint {
     pub fn set_color(&mut self, r: u8, g: u8, b: u8, a: u8) {
         self.0.set_argb(a, r, g, b);
     }
+    pub fn set_color_value(&mut self, color: &Color) {
+        self.0.set_color(color.to_skia_color());
+    }
     pub fn set_alpha(&mut self, a: u8) {
         self.0.set_alpha(a);
     }
@@ -337,6 +988,10 @@ impl Paint {
     pub fn set_shader(&mut self, shader: &Shader) {
         self.0.set_shader(Some(shader.0.clone()));
     }
+    /// Removes the shader, so the paint color will be used again.
+    pub fn clear_shader(&mut self) {
+        self.0.set_shader(None);
+    }
     pub fn set_stroke_width(&mut self, width: f64) {
         self.0.set_stroke_width(width as f32);
     }
@@ -352,6 +1007,27 @@ impl Paint {
     pub fn set_path_effect(&mut self, path_effect: PathEffect) {
         self.0.set_path_effect(Some(path_effect.0.clone()));
     }
+    pub fn set_color_filter(&mut self, filter: &ColorFilter) {
+        self.0.set_color_filter(Some(filter.0.clone()));
+    }
+    /// Resets all properties to defaults.
+    ///
+    /// Drops shader, path effect and color filter references.
+    pub fn reset(&mut self) {
+        self.0 = skia_safe::Paint::default();
+    }
+    pub fn is_anti_alias(&self) -> bool {
+        self.0.is_anti_alias()
+    }
+    pub fn blend_mode(&self) -> BlendMode {
+        BlendMode::from_skia(self.0.blend_mode())
+    }
+    pub fn style(&self) -> PaintStyle {
+        PaintStyle::from_skia(self.0.style())
+    }
+    pub fn stroke_width(&self) -> f64 {
+        self.0.stroke_width() as f64
+    }
 }
 
 impl Drop for Paint {
@@ -401,6 +1077,61 @@ pub struct Gradient {
     pub matrix: Matrix
 }
 
+impl Gradient {
+    /// Checks that the gradient can be converted into a shader.
+    pub fn validate(&self) -> Result<(), GradientError> {
+        if self.colors.len() != self.positions.len() {
+            return Err(GradientError::StopsMismatch);
+        }
+
+        if self.colors.len() < 2 {
+            return Err(GradientError::NotEnoughStops);
+        }
+
+        let mut prev = 0.0;
+        for &pos in &self.positions {
+            if !(0.0..=1.0).contains(&pos) || pos < prev {
+                return Err(GradientError::InvalidPositions);
+            }
+
+            prev = pos;
+        }
+
+        Ok(())
+    }
+}
+
+/// A gradient validation error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum GradientError {
+    /// Colors and positions have different lengths.
+    StopsMismatch,
+
+    /// Less than two stops.
+    NotEnoughStops,
+
+    /// Positions are not increasing or are outside the 0..1 range.
+    InvalidPositions,
+}
+
+impl std::fmt::Display for GradientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            GradientError::StopsMismatch => {
+                write!(f, "colors and positions have different lengths")
+            }
+            GradientError::NotEnoughStops => {
+                write!(f, "a gradient must have at least two stops")
+            }
+            GradientError::InvalidPositions => {
+                write!(f, "gradient positions must be increasing and in a 0..1 range")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GradientError {}
+
 pub struct LinearGradient {
     pub start_point: (f64, f64),
     pub end_point: (f64, f64),
@@ -416,30 +1147,40 @@ pub struct RadialGradient {
 pub struct Shader(skia_safe::Shader);
 
 impl Shader {
-    pub fn new_linear_gradient(grad:  LinearGradient) -> Shader {
+    /// Creates a linear gradient shader.
+    ///
+    /// Returns an error when the gradient doesn't pass `Gradient::validate`.
+    pub fn new_linear_gradient(grad: LinearGradient) -> Result<Shader, GradientError> {
+        grad.base.validate()?;
+
         let points = ((grad.start_point.0 as f32, grad.start_point.1 as f32), (grad.end_point.0 as f32, grad.end_point.1 as f32));
         let colors_list: Vec<skia_safe::Color> = grad.base.colors.into_iter().map(|color| skia_safe::Color::new(color)).collect();
         let colors = skia_safe::gradient_shader::GradientShaderColors::Colors(&colors_list);
         let positions = Some(grad.base.positions.as_slice());
         let tile_mode = grad.base.tile_mode.to_skia();
         let matrix = &grad.base.matrix.0;
-        Shader(skia_safe::Shader::linear_gradient(
+        Ok(Shader(skia_safe::Shader::linear_gradient(
             points,
             colors,
             positions,
             tile_mode,
             None,
             matrix,
-        ).unwrap())
+        ).unwrap()))
     }
 
-    pub fn new_radial_gradient(grad: RadialGradient) -> Shader {
+    /// Creates a two point conical gradient shader.
+    ///
+    /// Returns an error when the gradient doesn't pass `Gradient::validate`.
+    pub fn new_radial_gradient(grad: RadialGradient) -> Result<Shader, GradientError> {
+        grad.base.validate()?;
+
         let colors_list: Vec<skia_safe::Color> = grad.base.colors.into_iter().map(|color| skia_safe::Color::new(color)).collect();
         let colors = skia_safe::gradient_shader::GradientShaderColors::Colors(&colors_list);
         let positions = Some(grad.base.positions.as_slice());
         let tile_mode = grad.base.tile_mode.to_skia();
         let matrix = &grad.base.matrix.0;
-        Shader(skia_safe::Shader::two_point_conical_gradient(
+        Ok(Shader(skia_safe::Shader::two_point_conical_gradient(
             (grad.start_circle.0 as f32, grad.start_circle.1 as f32),
             grad.start_circle.2 as f32,
             (grad.end_circle.0 as f32, grad.end_circle.1 as f32),
@@ -449,15 +1190,56 @@ impl Shader {
             tile_mode,
             None,
             matrix,
-        ).unwrap())
+        ).unwrap()))
+    }
+
+    /// Creates a radial gradient with the focal point at the center.
+    ///
+    /// Faster than `new_radial_gradient` and has no focal point artifacts.
+    ///
+    /// Returns an error when the gradient doesn't pass `Gradient::validate`.
+    pub fn new_radial_gradient_simple(
+        center: (f64, f64),
+        radius: f64,
+        base: Gradient,
+    ) -> Result<Shader, GradientError> {
+        base.validate()?;
+
+        let colors_list: Vec<skia_safe::Color> = base.colors.into_iter().map(|color| skia_safe::Color::new(color)).collect();
+        let colors = skia_safe::gradient_shader::GradientShaderColors::Colors(&colors_list);
+        let positions = Some(base.positions.as_slice());
+        let tile_mode = base.tile_mode.to_skia();
+        let matrix = &base.matrix.0;
+        Ok(Shader(skia_safe::Shader::radial_gradient(
+            (center.0 as f32, center.1 as f32),
+            radius as f32,
+            colors,
+            positions,
+            tile_mode,
+            None,
+            matrix,
+        ).unwrap()))
     }
 
     pub fn new_from_surface_image(surface: &Surface, matrix: Matrix) -> Shader {
+        Shader::new_from_surface_image_tiled(surface, matrix, TileMode::Repeat, TileMode::Repeat)
+    }
+
+    /// Like `new_from_surface_image`, but with explicit tile modes for each axis.
+    pub fn new_from_surface_image_tiled(surface: &Surface, matrix: Matrix,
+                                        tile_x: TileMode, tile_y: TileMode) -> Shader {
         Shader(surface.image_snapshot().to_shader(
-            (skia_safe::TileMode::Repeat, skia_safe::TileMode::Repeat),
+            (tile_x.to_skia(), tile_y.to_skia()),
             Some(&matrix.0),
         ))
     }
+
+    /// Returns a copy of the shader with an additional local matrix.
+    ///
+    /// The matrix is composed with the one set during the shader creation.
+    pub fn with_local_matrix(&self, matrix: &Matrix) -> Shader {
+        Shader(self.0.with_local_matrix(&matrix.0))
+    }
 }
 
 impl Drop for Shader {
@@ -466,12 +1248,61 @@ impl Drop for Shader {
     }
 }
 
+/// A color filter that can be applied to a `Paint`.
+#[derive(Clone)]
+pub struct ColorFilter(skia_safe::ColorFilter);
+
+impl ColorFilter {
+    /// Creates a 4x5 row-major color matrix filter.
+    ///
+    /// Unlike `feColorMatrix`, the last column is in a 0..1 range and not 0..255.
+    pub fn matrix(matrix: &[f32; 20]) -> ColorFilter {
+        ColorFilter(skia_safe::color_filters::matrix_row_major(matrix))
+    }
+
+    /// Creates a filter that blends a constant ARGB `color` with the source using `mode`.
+    ///
+    /// Returns `None` when the filter will have no effect.
+    pub fn blend(color: u32, mode: BlendMode) -> Option<ColorFilter> {
+        skia_safe::color_filters::blend(skia_safe::Color::new(color), mode.to_skia())
+            .map(ColorFilter)
+    }
+
+    /// Creates a filter that applies `inner` first and then `outer`.
+    pub fn compose(outer: &ColorFilter, inner: &ColorFilter) -> Option<ColorFilter> {
+        skia_safe::color_filters::compose(outer.0.clone(), inner.0.clone()).map(ColorFilter)
+    }
+}
+
 pub struct PathEffect(skia_safe::PathEffect);
 
 impl PathEffect {
     pub fn new_dash_path(intervals: &[f32], phase: f32) -> PathEffect {
         PathEffect(skia_safe::PathEffect::dash(intervals, phase).unwrap())
     }
+
+    /// Applies the effect to a path geometry.
+    ///
+    /// `stroke` describes how the resulting path is meant to be drawn,
+    /// since some effects depend on it. `None` means a fill.
+    /// Note that the stroke itself is not applied, i.e. a dashed path
+    /// will still be a set of lines and not their outlines.
+    ///
+    /// Returns `None` when the effect cannot be applied to this path.
+    pub fn filter_path(&self, src: &Path, stroke: Option<StrokeParams>) -> Option<Path> {
+        let mut rec = skia_safe::StrokeRec::new(skia_safe::stroke_rec::InitStyle::Fill);
+        let mut outset = 0.0;
+        if let Some(stroke) = stroke {
+            rec.set_stroke_style(stroke.width as f32, false);
+            rec.set_stroke_params(stroke.cap.to_skia(), stroke.join.to_skia(),
+                                  stroke.miter_limit as f32);
+            outset = stroke.width as f32;
+        }
+
+        let cull_rect = src.0.bounds().with_outset((outset, outset));
+        let (path, _) = self.0.filter_path(&src.0, &rec, cull_rect)?;
+        Some(Path(path))
+    }
 }
 
 impl Drop for PathEffect {
@@ -480,12 +1311,127 @@ impl Drop for PathEffect {
     }
 }
 
+/// Font metrics scaled to the font size.
+///
+/// Like in Skia, `ascent` is negative, since it's above the baseline,
+/// and `descent` is positive.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FontMetrics {
+    pub ascent: f64,
+    pub descent: f64,
+    pub leading: f64,
+    pub cap_height: f64,
+}
+
+impl FontMetrics {
+    /// Returns a distance between two consecutive baselines.
+    pub fn line_height(&self) -> f64 {
+        self.descent - self.ascent + self.leading
+    }
+}
+
+/// A typeface with a size.
+///
+/// Only draws glyphs as is. Text shaping and layout are done by usvg.
+pub struct Font(skia_safe::Font);
+
+impl Font {
+    pub fn from_family(family: &str, size: f64) -> Option<Font> {
+        let typeface = skia_safe::Typeface::from_name(family, skia_safe::FontStyle::normal())?;
+        Some(Font(skia_safe::Font::from_typeface(&typeface, size as f32)))
+    }
+
+    pub fn from_data(data: &[u8], index: usize, size: f64) -> Option<Font> {
+        let data = skia_safe::Data::new_copy(data);
+        let typeface = skia_safe::Typeface::from_data(data, index)?;
+        Some(Font(skia_safe::Font::from_typeface(&typeface, size as f32)))
+    }
+
+    /// Returns the text advance width.
+    pub fn measure_str(&self, text: &str) -> f64 {
+        let (advance, _) = self.0.measure_str(text, None);
+        advance as f64
+    }
+
+    /// Returns font metrics for the current size.
+    ///
+    /// A two-line layout:
+    ///
+    /// ```ignore
+    /// let metrics = font.metrics();
+    /// let first_baseline = y - metrics.ascent;
+    /// let second_baseline = first_baseline + metrics.line_height();
+    /// canvas.draw_str("First line", x, first_baseline, &font, &paint);
+    /// canvas.draw_str("Second line", x, second_baseline, &font, &paint);
+    /// ```
+    pub fn metrics(&self) -> FontMetrics {
+        let (_, metrics) = self.0.metrics();
+        FontMetrics {
+            ascent: metrics.ascent as f64,
+            descent: metrics.descent as f64,
+            leading: metrics.leading as f64,
+            cap_height: metrics.cap_height as f64,
+        }
+    }
+
+    /// Returns a glyph outline.
+    ///
+    /// Returns `None` for a missing glyph.
+    ///
+    /// Outlines can be cached and drawn via `Canvas::draw_path`:
+    ///
+    /// ```ignore
+    /// let mut cache: HashMap<u16, Path> = HashMap::new();
+    /// if !cache.contains_key(&glyph_id) {
+    ///     if let Some(path) = font.get_path(glyph_id) {
+    ///         cache.insert(glyph_id, path);
+    ///     }
+    /// }
+    /// ```
+    pub fn get_path(&self, glyph_id: u16) -> Option<Path> {
+        self.0.get_path(glyph_id).map(Path)
+    }
+
+    /// Returns a character outline.
+    ///
+    /// Returns `None` when the font doesn't have a glyph for this character.
+    pub fn get_char_path(&self, c: char) -> Option<Path> {
+        let glyph_id = self.0.unichar_to_glyph(c as i32);
+        if glyph_id == 0 {
+            return None;
+        }
+
+        self.get_path(glyph_id)
+    }
+}
+
+impl Drop for Font {
+    fn drop(&mut self) {
+        // Implemented by Skia-safe.
+    }
+}
+
+fn solid_fill_paint(r: u8, g: u8, b: u8, a: u8) -> skia_safe::Paint {
+    let mut paint = skia_safe::Paint::default();
+    paint.set_style(skia_safe::PaintStyle::Fill);
+    paint.set_anti_alias(true);
+    paint.set_argb(a, r, g, b);
+    paint
+}
+
 // New Bindings additions
 
 impl Surface {
-    fn new_rgba_impl(width: u32, height: u32, alpha_type: skia_safe::AlphaType) -> Option<Surface> {
+    fn new_rgba_impl(
+        width: u32,
+        height: u32,
+        alpha_type: skia_safe::AlphaType,
+        color_space: Option<skia_safe::ColorSpace>,
+    ) -> Option<Surface> {
         let size = skia_safe::ISize::new(width as i32, height as i32);
-        let image_info = skia_safe::ImageInfo::new(size, skia_safe::ColorType::n32(), alpha_type, None);
+        let image_info = skia_safe::ImageInfo::new(
+            size, skia_safe::ColorType::n32(), alpha_type, color_space,
+        );
         let surface = skia_safe::Surface::new_raster(&image_info, None, None).unwrap();
         let canvas = Canvas(surface.clone());
         Some(Surface { surface, canvas })
@@ -515,6 +1461,17 @@ impl ToSkia<skia_safe::BlendMode> for BlendMode {
             BlendMode::Screen => skia_safe::BlendMode::Screen,
             BlendMode::Darken => skia_safe::BlendMode::Darken,
             BlendMode::Lighten => skia_safe::BlendMode::Lighten,
+            BlendMode::Plus => skia_safe::BlendMode::Plus,
+        }
+    }
+}
+
+impl ToSkia<skia_safe::EncodedImageFormat> for ImageFormat {
+    fn to_skia(&self) -> skia_safe::EncodedImageFormat {
+        match self {
+            ImageFormat::Png => skia_safe::EncodedImageFormat::PNG,
+            ImageFormat::Jpeg => skia_safe::EncodedImageFormat::JPEG,
+            ImageFormat::Webp => skia_safe::EncodedImageFormat::WEBP,
         }
     }
 }
@@ -524,6 +1481,43 @@ impl ToSkia<skia_safe::PaintStyle> for PaintStyle {
         match self {
             PaintStyle::Fill => skia_safe::PaintStyle::Fill,
             PaintStyle::Stroke => skia_safe::PaintStyle::Stroke,
+            PaintStyle::StrokeAndFill => skia_safe::PaintStyle::StrokeAndFill,
+        }
+    }
+}
+
+trait FromSkia<SkType> {
+    fn from_skia(value: SkType) -> Self;
+}
+
+impl FromSkia<skia_safe::BlendMode> for BlendMode {
+    fn from_skia(value: skia_safe::BlendMode) -> Self {
+        match value {
+            skia_safe::BlendMode::Clear => BlendMode::Clear,
+            skia_safe::BlendMode::SrcOver => BlendMode::SourceOver,
+            skia_safe::BlendMode::DstOver => BlendMode::DestinationOver,
+            skia_safe::BlendMode::SrcIn => BlendMode::SourceIn,
+            skia_safe::BlendMode::DstIn => BlendMode::DestinationIn,
+            skia_safe::BlendMode::SrcOut => BlendMode::SourceOut,
+            skia_safe::BlendMode::DstOut => BlendMode::DestinationOut,
+            skia_safe::BlendMode::SrcATop => BlendMode::SourceAtop,
+            skia_safe::BlendMode::Xor => BlendMode::Xor,
+            skia_safe::BlendMode::Multiply => BlendMode::Multiply,
+            skia_safe::BlendMode::Screen => BlendMode::Screen,
+            skia_safe::BlendMode::Darken => BlendMode::Darken,
+            skia_safe::BlendMode::Lighten => BlendMode::Lighten,
+            skia_safe::BlendMode::Plus => BlendMode::Plus,
+            _ => BlendMode::SourceOver,
+        }
+    }
+}
+
+impl FromSkia<skia_safe::PaintStyle> for PaintStyle {
+    fn from_skia(value: skia_safe::PaintStyle) -> Self {
+        match value {
+            skia_safe::PaintStyle::Fill => PaintStyle::Fill,
+            skia_safe::PaintStyle::Stroke => PaintStyle::Stroke,
+            skia_safe::PaintStyle::StrokeAndFill => PaintStyle::StrokeAndFill,
         }
     }
 }
@@ -577,3 +1571,501 @@ impl ToSkia<skia_safe::FilterQuality> for FilterQuality {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render_order(order: PaintOrder) -> (u8, u8, u8) {
+        let mut surface = Surface::new_rgba_premultiplied(20, 20).unwrap();
+
+        let mut path = Path::new();
+        path.move_to(5.0, 5.0);
+        path.line_to(15.0, 5.0);
+        path.line_to(15.0, 15.0);
+        path.line_to(5.0, 15.0);
+        path.close();
+
+        let mut fill = Paint::new();
+        fill.set_style(PaintStyle::Fill);
+        fill.set_color(255, 0, 0, 255);
+
+        let mut stroke = Paint::new();
+        stroke.set_style(PaintStyle::Stroke);
+        stroke.set_stroke_width(4.0);
+        stroke.set_color(0, 0, 255, 255);
+
+        surface.draw_path_fill_stroke(&path, &fill, &stroke, order);
+
+        // A pixel inside the fill, but covered by the inner half of the stroke.
+        let idx = (6 * 20 + 10) * 4;
+        let data = surface.data();
+        if Surface::is_bgra() {
+            (data[idx + 2], data[idx + 1], data[idx])
+        } else {
+            (data[idx], data[idx + 1], data[idx + 2])
+        }
+    }
+
+    #[test]
+    fn paint_reset() {
+        let mut paint = Paint::new();
+        paint.set_color(255, 0, 0, 255);
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_path_effect(PathEffect::new_dash_path(&[1.0, 1.0], 0.0));
+        paint.set_shader(&Shader(skia_safe::Shader::color(skia_safe::Color::RED)));
+        paint.0.set_color_filter(Some(skia_safe::color_filters::linear_to_srgb_gamma()));
+
+        paint.reset();
+        assert_eq!(paint.style(), PaintStyle::Fill);
+        assert!(paint.0.shader().is_none());
+        assert!(paint.0.path_effect().is_none());
+        assert!(paint.0.color_filter().is_none());
+    }
+
+    #[test]
+    fn paint_order_fill_first() {
+        assert_eq!(render_order(PaintOrder::FillFirst), (0, 0, 255));
+    }
+
+    #[test]
+    fn paint_order_stroke_first() {
+        assert_eq!(render_order(PaintOrder::StrokeFirst), (255, 0, 0));
+    }
+
+    #[test]
+    fn image_nine_center_validation() {
+        let src = Surface::new_rgba_premultiplied(10, 10).unwrap();
+        let mut surface = Surface::new_rgba_premultiplied(40, 40).unwrap();
+        let dst = (0.0, 0.0, 40.0, 40.0);
+
+        assert!(surface.draw_image_nine(&src, (3.0, 3.0, 4.0, 4.0), dst, FilterQuality::Low));
+        assert!(!surface.draw_image_nine(&src, (3.0, 3.0, 0.0, 4.0), dst, FilterQuality::Low));
+        assert!(!surface.draw_image_nine(&src, (8.0, 3.0, 4.0, 4.0), dst, FilterQuality::Low));
+        assert!(!surface.draw_image_nine(&src, (-1.0, 3.0, 4.0, 4.0), dst, FilterQuality::Low));
+    }
+
+    #[test]
+    fn dash_filter_path() {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(10.0, 0.0);
+
+        let effect = PathEffect::new_dash_path(&[2.0, 2.0], 0.0);
+        let stroke = StrokeParams {
+            width: 1.0,
+            cap: StrokeCap::Butt,
+            join: StrokeJoin::Miter,
+            miter_limit: 4.0,
+        };
+
+        let dashed = effect.filter_path(&path, Some(stroke)).unwrap();
+        // Three dashes, each with a MoveTo and a LineTo.
+        assert_eq!(dashed.0.count_points(), 6);
+    }
+
+    #[test]
+    fn resize_surface() {
+        let mut surface = Surface::new_rgba_premultiplied(20, 10).unwrap();
+        surface.fill(0, 0, 255, 255);
+
+        let resized = surface.resize(5, 4, FilterQuality::High).unwrap();
+        assert_eq!((resized.width(), resized.height()), (5, 4));
+        assert_eq!(resized.data()[3], 255);
+
+        assert!(surface.resize(0, 4, FilterQuality::High).is_none());
+    }
+
+    #[test]
+    fn png_is_always_supported() {
+        assert!(Surface::supports_format(ImageFormat::Png));
+        // Cached.
+        assert!(Surface::supports_format(ImageFormat::Png));
+    }
+
+    #[test]
+    fn snapshot_is_copy_on_write() {
+        let mut src = Surface::new_rgba_premultiplied(2, 2).unwrap();
+        src.fill(255, 0, 0, 255);
+        let image = src.snapshot();
+        src.fill(0, 0, 255, 255);
+        assert_eq!((image.width(), image.height()), (2, 2));
+
+        let mut dst = Surface::new_rgba_premultiplied(4, 2).unwrap();
+        dst.draw_image_handle(&image, 0.0, 0.0, 255, BlendMode::SourceOver, FilterQuality::None);
+        dst.draw_image_handle(&image, 2.0, 0.0, 255, BlendMode::SourceOver, FilterQuality::None);
+
+        let data = dst.data();
+        let red = if Surface::is_bgra() { 2 } else { 0 };
+        for x in 0..4 {
+            assert_eq!(data[x * 4 + red], 255);
+            assert_eq!(data[x * 4 + 3], 255);
+        }
+    }
+
+    #[test]
+    fn apply_opacity() {
+        let mut surface = Surface::new_rgba_premultiplied(2, 2).unwrap();
+        surface.fill(255, 0, 0, 255);
+        surface.apply_opacity(128);
+
+        let data = surface.data();
+        for pixel in data.chunks_exact(4) {
+            assert!((pixel[3] as i32 - 128).abs() <= 1);
+        }
+
+        let mut surface = Surface::new_rgba_premultiplied(2, 2).unwrap();
+        surface.apply_opacity(128);
+        assert!(surface.data().iter().all(|c| *c == 0));
+    }
+
+    #[test]
+    fn dashes_are_capped() {
+        let mut surface = Surface::new_rgba_premultiplied(40, 20).unwrap();
+
+        let mut path = Path::new();
+        path.move_to(0.0, 10.0);
+        path.line_to(40.0, 10.0);
+
+        let mut paint = Paint::new();
+        paint.set_style(PaintStyle::Stroke);
+        paint.set_anti_alias(false);
+        paint.set_color(0, 0, 0, 255);
+        paint.set_stroke_width(6.0);
+        paint.set_path_effect(PathEffect::new_dash_path(&[10.0, 10.0], 0.0));
+        paint.set_stroke_cap(StrokeCap::Round);
+        surface.draw_path(&path, &paint);
+
+        let alpha = |x: usize, y: usize| surface.data()[(y * 40 + x) * 4 + 3];
+        // Inside the first dash cap.
+        assert_eq!(alpha(11, 10), 255);
+        // Outside the cap circle, but inside a square cap.
+        assert_eq!(alpha(12, 7), 0);
+        // Between dashes.
+        assert_eq!(alpha(15, 10), 0);
+    }
+
+    #[test]
+    fn matrix_scale_factor() {
+        fn check(m: Matrix, det: f64, scale: f64) {
+            assert!((m.determinant() - det).abs() < 1e-6);
+            assert!((m.scale_factor() - scale).abs() < 1e-6);
+        }
+
+        check(Matrix::new(), 1.0, 1.0);
+        check(Matrix::new_from(2.0, 0.0, 0.0, 8.0, 10.0, 20.0), 16.0, 4.0);
+        check(Matrix::new_from(-3.0, 0.0, 0.0, 3.0, 0.0, 0.0), -9.0, 3.0);
+
+        // A rotation by 30 degrees with a scale of 2.
+        let (sin, cos) = (30f64.to_radians().sin() * 2.0, 30f64.to_radians().cos() * 2.0);
+        check(Matrix::new_from(cos, sin, -sin, cos, 0.0, 0.0), 4.0, 2.0);
+
+        check(Matrix::new_from(1.0, 2.0, 2.0, 4.0, 0.0, 0.0), 0.0, 0.0);
+        check(Matrix::new_from(0.0, 0.0, 0.0, 0.0, 0.0, 0.0), 0.0, 0.0);
+    }
+
+    #[test]
+    fn draw_rect_tiled() {
+        let mut tile = Surface::new_rgba_premultiplied(2, 1).unwrap();
+        tile.fill_rect(0.0, 0.0, 1.0, 1.0, 255, 0, 0, 255);
+
+        let mut surface = Surface::new_rgba_premultiplied(8, 2).unwrap();
+        surface.draw_rect_tiled((0.0, 0.0, 6.0, 2.0), &tile, Matrix::new(),
+                                TileMode::Repeat, TileMode::Repeat);
+
+        let data = surface.data();
+        for y in 0..2 {
+            for x in 0..8 {
+                let alpha = data[(y * 8 + x) * 4 + 3];
+                let expected = if x < 6 && x % 2 == 0 { 255 } else { 0 };
+                assert_eq!(alpha, expected, "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn save_layer_bounds() {
+        let mut surface = Surface::new_rgba_premultiplied(4, 4).unwrap();
+        surface.fill(0, 0, 255, 255);
+
+        // Draw across the whole surface. Only the layer bounds must be affected.
+        surface.save_layer_bounds((0.0, 0.0, 2.0, 2.0), 128);
+        surface.fill_rect(0.0, 0.0, 4.0, 4.0, 255, 0, 0, 255);
+        surface.restore();
+
+        let data = surface.data();
+        let (red, blue) = if Surface::is_bgra() { (2, 0) } else { (0, 2) };
+        let pixel = |x: usize, y: usize| &data[(y * 4 + x) * 4..(y * 4 + x + 1) * 4];
+
+        for y in 0..4 {
+            for x in 0..4 {
+                let p = pixel(x, y);
+                if x < 2 && y < 2 {
+                    // Red with the layer alpha over blue.
+                    assert!((p[red] as i32 - 128).abs() <= 1);
+                    assert!((p[blue] as i32 - 127).abs() <= 1);
+                } else {
+                    assert_eq!((p[red], p[blue]), (0, 255));
+                }
+                assert_eq!((p[1], p[3]), (0, 255));
+            }
+        }
+    }
+
+    #[test]
+    fn color_filter_compose() {
+        // Keeps only the red channel.
+        let red_only = ColorFilter::matrix(&[
+            1.0, 0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0, 0.0,
+        ]);
+        // Replaces the source color with blue, keeping its alpha.
+        let blue = ColorFilter::blend(Color::new(255, 0, 0, 255).to_u32(), BlendMode::SourceIn).unwrap();
+
+        let draw = |filter: &ColorFilter| {
+            let mut surface = Surface::new_rgba_premultiplied(1, 1).unwrap();
+            let mut paint = Paint::new();
+            paint.set_color(255, 255, 255, 255);
+            paint.set_color_filter(filter);
+            surface.draw_rect(0.0, 0.0, 1.0, 1.0, &paint);
+
+            let data = surface.data();
+            if Surface::is_bgra() {
+                (data[2], data[1], data[0], data[3])
+            } else {
+                (data[0], data[1], data[2], data[3])
+            }
+        };
+
+        assert_eq!(draw(&red_only), (255, 0, 0, 255));
+        assert_eq!(draw(&ColorFilter::compose(&red_only, &blue).unwrap()), (0, 0, 0, 255));
+        assert_eq!(draw(&ColorFilter::compose(&blue, &red_only).unwrap()), (0, 0, 255, 255));
+    }
+
+    #[test]
+    fn draw_path_clipped() {
+        let mut surface = Surface::new_rgba_premultiplied(4, 4).unwrap();
+
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        path.line_to(4.0, 0.0);
+        path.line_to(4.0, 4.0);
+        path.line_to(0.0, 4.0);
+        path.close();
+
+        let mut clip = Path::new();
+        clip.move_to(0.0, 0.0);
+        clip.line_to(2.0, 0.0);
+        clip.line_to(2.0, 4.0);
+        clip.line_to(0.0, 4.0);
+        clip.close();
+
+        let mut paint = Paint::new();
+        paint.set_color(0, 0, 0, 255);
+        surface.draw_path_clipped(&path, &paint, &clip);
+
+        let data = surface.data();
+        for y in 0..4 {
+            for x in 0..4 {
+                let expected = if x < 2 { 255 } else { 0 };
+                assert_eq!(data[(y * 4 + x) * 4 + 3], expected);
+            }
+        }
+
+        // The clip must not leak.
+        assert_eq!(surface.clip_bounds(), Some((0.0, 0.0, 4.0, 4.0)));
+    }
+
+    #[test]
+    fn clip_bounds() {
+        fn bounds(canvas: &Canvas) -> Option<(f64, f64, f64, f64)> {
+            canvas.clip_bounds()
+        }
+
+        let mut surface = Surface::new_rgba_premultiplied(4, 4).unwrap();
+        surface.save();
+        surface.scale(2.0, 2.0);
+        surface.set_clip_rect(0.5, 0.0, 1.0, 1.0);
+        assert_eq!(bounds(&surface), Some((1.0, 0.0, 2.0, 2.0)));
+
+        surface.set_clip_rect(3.0, 3.0, 1.0, 1.0);
+        assert_eq!(bounds(&surface), None);
+
+        surface.restore();
+        assert_eq!(bounds(&surface), Some((0.0, 0.0, 4.0, 4.0)));
+    }
+
+    #[test]
+    fn clear_rect() {
+        let mut surface = Surface::new_rgba_premultiplied(4, 4).unwrap();
+        surface.fill(0, 0, 255, 255);
+        surface.scale(2.0, 2.0);
+        surface.clear_rect(1, 1, 2, 2, 0, 0, 0, 0);
+
+        let data = surface.data();
+        for y in 0..4 {
+            for x in 0..4 {
+                let inside = (1..3).contains(&x) && (1..3).contains(&y);
+                let expected = if inside { 0 } else { 255 };
+                assert_eq!(data[(y * 4 + x) * 4 + 3], expected);
+            }
+        }
+    }
+
+    #[cfg(feature = "usvg")]
+    #[test]
+    fn color_from_usvg() {
+        let red = usvg::Color::new(255, 0, 0);
+        assert_eq!(Color::from_usvg(red, 0.5).to_u32(), 0x7FFF0000);
+        assert_eq!(Color::from_usvg(red, 2.0).to_u32(), 0xFFFF0000);
+        assert_eq!(Color::from_usvg(red, -1.0).to_u32(), 0x00FF0000);
+
+        let mut surface = Surface::new_rgba_premultiplied(1, 1).unwrap();
+        let mut paint = Paint::new();
+        paint.set_color_value(&Color::from_usvg(red, 0.5));
+        surface.draw_rect(0.0, 0.0, 1.0, 1.0, &paint);
+        let data = surface.data();
+        // Red and blue may be swapped depending on the native color type.
+        assert_eq!(data[0].max(data[2]), 127);
+        assert_eq!(data[3], 127);
+    }
+
+    fn decode_solid(format: ImageFormat) {
+        let mut surface = Surface::new_rgba_premultiplied(4, 3).unwrap();
+        surface.fill(0, 0, 255, 255);
+        let bytes = surface.encode(format, 100).unwrap();
+
+        let decoded = Surface::from_encoded(&bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (4, 3));
+        let data = decoded.data();
+        let (r, b) = if Surface::is_bgra() { (data[2], data[0]) } else { (data[0], data[2]) };
+        assert!(r < 8 && b > 247 && data[3] == 255);
+    }
+
+    #[test]
+    fn from_encoded_png() {
+        decode_solid(ImageFormat::Png);
+    }
+
+    #[test]
+    fn from_encoded_jpeg() {
+        // JPEG encoder is optional.
+        if Surface::supports_format(ImageFormat::Jpeg) {
+            decode_solid(ImageFormat::Jpeg);
+        }
+    }
+
+    #[test]
+    fn from_encoded_invalid() {
+        assert!(Surface::from_encoded(b"not an image").is_none());
+        assert!(Surface::from_encoded(&[]).is_none());
+    }
+
+    #[test]
+    fn clear_shader() {
+        let mut tile = Surface::new_rgba_premultiplied(1, 1).unwrap();
+        tile.fill(0, 0, 255, 255);
+
+        let mut surface = Surface::new_rgba_premultiplied(2, 2).unwrap();
+        let mut paint = Paint::new();
+        paint.set_shader(&Shader::new_from_surface_image(&tile, Matrix::new()));
+        paint.set_color(255, 0, 0, 255);
+        paint.clear_shader();
+        surface.draw_rect(0.0, 0.0, 2.0, 2.0, &paint);
+
+        let data = surface.data();
+        let (r, b) = if Surface::is_bgra() { (data[2], data[0]) } else { (data[0], data[2]) };
+        assert_eq!((r, b, data[3]), (255, 0, 255));
+    }
+
+    #[test]
+    fn paint_clone_outlives_originals() {
+        let paint = {
+            let mut tile = Surface::new_rgba_premultiplied(1, 1).unwrap();
+            tile.fill(0, 0, 255, 255);
+            let shader = Shader::new_from_surface_image(&tile, Matrix::new());
+
+            let mut paint = Paint::new();
+            paint.set_style(PaintStyle::Stroke);
+            paint.set_anti_alias(false);
+            paint.set_stroke_width(2.0);
+            paint.set_shader(&shader);
+            paint.set_path_effect(PathEffect::new_dash_path(&[2.0, 2.0], 0.0));
+
+            // The tile, the shader and the original paint are dropped here.
+            paint.clone()
+        };
+
+        let mut surface = Surface::new_rgba_premultiplied(8, 2).unwrap();
+        let mut path = Path::new();
+        path.move_to(0.0, 1.0);
+        path.line_to(8.0, 1.0);
+        surface.draw_path(&path, &paint);
+
+        let data = surface.data();
+        let b = if Surface::is_bgra() { 0 } else { 2 };
+        for x in 0..8 {
+            let idx = (8 + x) * 4;
+            let expected = if x % 4 < 2 { 255 } else { 0 };
+            assert_eq!((data[idx + b], data[idx + 3]), (expected, expected));
+        }
+    }
+
+    #[test]
+    fn wrap_pixels() {
+        assert!(Surface::wrap_pixels(2, 2, 7, &mut [0; 16]).is_none());
+        assert!(Surface::wrap_pixels(2, 2, 8, &mut [0; 15]).is_none());
+        assert!(Surface::wrap_pixels(0, 2, 8, &mut [0; 16]).is_none());
+
+        // A padded row.
+        let mut data = vec![0; 12 * 2];
+        {
+            let mut surface = Surface::wrap_pixels(2, 2, 12, &mut data).unwrap();
+            surface.fill(0, 0, 0, 255);
+            surface.clear_rect(0, 1, 1, 1, 0, 0, 0, 0);
+            assert_eq!(surface.width(), 2);
+        }
+
+        // The buffer is accessible again after the surface is dropped.
+        let alpha: Vec<u8> = data.chunks(4).map(|p| p[3]).collect();
+        assert_eq!(alpha, vec![255, 255, 0, 0, 255, 0]);
+    }
+
+    #[test]
+    fn invalid_gradient_shaders() {
+        fn base(colors: Vec<u32>, positions: Vec<f32>) -> Gradient {
+            Gradient { colors, positions, tile_mode: TileMode::Clamp, matrix: Matrix::new() }
+        }
+
+        fn check(colors: Vec<u32>, positions: Vec<f32>) -> Result<(), GradientError> {
+            let linear = Shader::new_linear_gradient(LinearGradient {
+                start_point: (0.0, 0.0),
+                end_point: (1.0, 0.0),
+                base: base(colors.clone(), positions.clone()),
+            }).map(|_| ());
+
+            let radial = Shader::new_radial_gradient(RadialGradient {
+                start_circle: (0.0, 0.0, 0.0),
+                end_circle: (1.0, 0.0, 1.0),
+                base: base(colors.clone(), positions.clone()),
+            }).map(|_| ());
+
+            let simple = Shader::new_radial_gradient_simple(
+                (0.0, 0.0), 1.0, base(colors, positions),
+            ).map(|_| ());
+
+            assert_eq!(linear, radial);
+            assert_eq!(linear, simple);
+            linear
+        }
+
+        let red = 0xFFFF0000;
+        let blue = 0xFF0000FF;
+        assert_eq!(check(vec![red, blue], vec![0.0, 1.0]), Ok(()));
+        assert_eq!(check(vec![red, blue], vec![0.0]), Err(GradientError::StopsMismatch));
+        assert_eq!(check(vec![red, blue], vec![0.6, 0.4]), Err(GradientError::InvalidPositions));
+        assert_eq!(check(vec![], vec![]), Err(GradientError::NotEnoughStops));
+    }
+}
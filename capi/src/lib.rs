@@ -423,7 +423,7 @@ pub extern "C" fn resvg_qt_render_to_canvas_by_id(
     }
 
     if let Some(node) = tree.0.node_by_id(id) {
-        if let Some(bbox) = node.calculate_bbox() {
+        if let Some(bbox) = resvg::utils::node_visual_bbox(&node) {
             let vbox = usvg::ViewBox {
                 rect: bbox,
                 aspect: usvg::AspectRatio::default(),
@@ -471,7 +471,7 @@ pub extern "C" fn resvg_cairo_render_to_canvas_by_id(
     });
 
     if let Some(node) = tree.0.node_by_id(id) {
-        if let Some(bbox) = node.calculate_bbox() {
+        if let Some(bbox) = resvg::utils::node_visual_bbox(&node) {
             let vbox = usvg::ViewBox {
                 rect: bbox,
                 aspect: usvg::AspectRatio::default(),
@@ -519,7 +519,7 @@ pub extern "C" fn resvg_skia_render_to_canvas_by_id(
     });
 
     if let Some(node) = tree.0.node_by_id(id) {
-        if let Some(bbox) = node.calculate_bbox() {
+        if let Some(bbox) = resvg::utils::node_visual_bbox(&node) {
             let vbox = usvg::ViewBox {
                 rect: bbox,
                 aspect: usvg::AspectRatio::default(),
@@ -757,11 +757,11 @@ fn to_native_opt(
     };
 
     let background = if opt.draw_background {
-        Some(usvg::Color::new(
+        Some(resvg::Background::Color(usvg::Color::new(
             opt.background.r,
             opt.background.g,
             opt.background.b,
-        ))
+        )))
     } else {
         None
     };
@@ -846,9 +846,16 @@ fn to_native_opt(
             text_rendering,
             image_rendering,
             keep_named_groups: opt.keep_named_groups,
+            image_href_resolver: None,
+            allow_external_resources: true,
+            fallback_paint: None,
         },
         fit_to,
         background,
+        linear_compositing: false,
+        supersample: 1,
+        max_render_depth: 256,
+        max_filter_region: 8192,
     }
 }
 
@@ -0,0 +1,129 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::prelude::*;
+
+
+/// An image position inside an atlas.
+#[derive(Clone, PartialEq, Debug)]
+pub struct AtlasEntry {
+    /// An image ID.
+    pub id: String,
+    /// A left edge position.
+    pub x: u32,
+    /// A top edge position.
+    pub y: u32,
+    /// An image width.
+    pub width: u32,
+    /// An image height.
+    pub height: u32,
+}
+
+/// A sprite atlas.
+pub struct Atlas {
+    /// An atlas size.
+    pub size: ScreenSize,
+    /// Unmultiplied RGBA pixels of the atlas.
+    ///
+    /// Rows are tightly packed. Unused areas are transparent.
+    pub data: Vec<u8>,
+    /// Packed images in the input order.
+    pub entries: Vec<AtlasEntry>,
+}
+
+/// Packs images into a single atlas.
+///
+/// Images keep their own sizes, i.e. they are not rescaled.
+/// `padding` is the number of transparent pixels between images.
+///
+/// Uses a simple shelf packer: images are sorted by height and placed
+/// in rows of an approximately square atlas.
+///
+/// Returns `None` when `images` is empty.
+pub fn pack_atlas(
+    images: &mut [(String, Box<dyn OutputImage>)],
+    padding: u32,
+) -> Option<Atlas> {
+    let sizes: Vec<ScreenSize> = images.iter().map(|(_, img)| img.size()).collect();
+    let (size, positions) = pack(&sizes, padding)?;
+
+    let stride = size.width() as usize * 4;
+    let mut data = vec![0; stride * size.height() as usize];
+    let mut entries = Vec::with_capacity(images.len());
+    for ((id, img), (x, y)) in images.iter_mut().zip(positions) {
+        let img_size = img.size();
+        let img_data = img.make_rgba_vec();
+        let img_stride = img_size.width() as usize * 4;
+        for (row_idx, row) in img_data.chunks_exact(img_stride).enumerate() {
+            let start = (y as usize + row_idx) * stride + x as usize * 4;
+            data[start..start + img_stride].copy_from_slice(row);
+        }
+
+        entries.push(AtlasEntry {
+            id: id.clone(),
+            x,
+            y,
+            width: img_size.width(),
+            height: img_size.height(),
+        });
+    }
+
+    Some(Atlas { size, data, entries })
+}
+
+/// Returns an atlas size and images positions in the input order.
+fn pack(
+    sizes: &[ScreenSize],
+    padding: u32,
+) -> Option<(ScreenSize, Vec<(u32, u32)>)> {
+    let area: u64 = sizes.iter()
+        .map(|s| (s.width() + padding) as u64 * (s.height() + padding) as u64)
+        .sum();
+    let max_width = sizes.iter().map(|s| s.width()).max()?;
+    let target_width = std::cmp::max(max_width, (area as f64).sqrt().ceil() as u32);
+
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|i| std::cmp::Reverse(sizes[*i].height()));
+
+    let mut positions = vec![(0, 0); sizes.len()];
+    let (mut x, mut y) = (0, 0);
+    let mut shelf_height = 0;
+    let mut width = 0;
+    for i in order {
+        let size = sizes[i];
+        if x != 0 && x + size.width() > target_width {
+            x = 0;
+            y += shelf_height + padding;
+            shelf_height = 0;
+        }
+
+        positions[i] = (x, y);
+        width = std::cmp::max(width, x + size.width());
+        shelf_height = std::cmp::max(shelf_height, size.height());
+        x += size.width() + padding;
+    }
+
+    let size = ScreenSize::new(width, y + shelf_height)?;
+    Some((size, positions))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shelf_packing() {
+        let sizes = [
+            ScreenSize::new(10, 10).unwrap(),
+            ScreenSize::new(20, 30).unwrap(),
+            ScreenSize::new(10, 10).unwrap(),
+            ScreenSize::new(20, 20).unwrap(),
+        ];
+
+        let (size, positions) = pack(&sizes, 2).unwrap();
+        assert_eq!(positions, vec![(22, 32), (0, 0), (0, 54), (0, 32)]);
+        assert_eq!(size.dimensions(), (32, 64));
+    }
+}
@@ -6,7 +6,6 @@ use std::cmp;
 use std::rc::Rc;
 
 use rgb::FromSlice;
-use log::warn;
 use usvg::ColorInterpolation as ColorSpace;
 
 use crate::prelude::*;
@@ -75,7 +75,7 @@ pub fn draw_raster(
     cr.reset_clip();
 }
 
-fn image_to_surface(image: &image::Image, surface: &mut [u8]) {
+pub fn image_to_surface(image: &image::Image, surface: &mut [u8]) {
     // Surface is always ARGB.
     const SURFACE_CHANNELS: usize = 4;
 
@@ -4,9 +4,7 @@
 
 //! Cairo backend implementation.
 
-use log::warn;
-
-use crate::{prelude::*, layers, ConvTransform, RenderState};
+use crate::{prelude::*, budget, layers, perf, ConvTransform, RenderDepthGuard, RenderState};
 
 
 macro_rules! try_create_surface {
@@ -76,7 +74,7 @@ impl Render for Backend {
         tree: &usvg::Tree,
         opt: &Options,
     ) -> Option<Box<dyn OutputImage>> {
-        let img = render_to_image(tree, opt)?;
+        let img = perf::trace_render(&tree.root(), || render_to_image(tree, opt))?;
         Some(Box::new(img))
     }
 
@@ -85,12 +83,16 @@ impl Render for Backend {
         node: &usvg::Node,
         opt: &Options,
     ) -> Option<Box<dyn OutputImage>> {
-        let img = render_node_to_image(node, opt)?;
+        let img = perf::trace_render(node, || render_node_to_image(node, opt))?;
         Some(Box::new(img))
     }
 }
 
 impl OutputImage for cairo::ImageSurface {
+    fn size(&self) -> ScreenSize {
+        ScreenSize::new(self.get_width() as u32, self.get_height() as u32).unwrap()
+    }
+
     fn save_png(
         &mut self,
         path: &std::path::Path,
@@ -128,6 +130,24 @@ impl OutputImage for cairo::ImageSurface {
 
         data
     }
+
+    fn make_rgba_vec_with_format(&mut self, format: PixelFormat) -> Vec<u8> {
+        use rgb::FromSlice;
+        use std::mem::swap;
+
+        match format {
+            PixelFormat::RgbaStraight => self.make_rgba_vec(),
+            PixelFormat::RgbaPremultiplied => {
+                // Return the internal data as is, without a lossy demultiplication.
+                let mut data = self.make_vec();
+
+                // BGRA_Premultiplied -> RGBA_Premultiplied.
+                data.as_bgra_mut().iter_mut().for_each(|p| swap(&mut p.r, &mut p.b));
+
+                data
+            }
+        }
+    }
 }
 
 
@@ -136,18 +156,14 @@ pub fn render_to_image(
     tree: &usvg::Tree,
     opt: &Options,
 ) -> Option<cairo::ImageSurface> {
-    let (surface, img_view) = create_surface(
+    let (mut surface, img_view) = create_surface(
         tree.svg_node().size.to_screen_size(),
         opt,
     )?;
 
-    let cr = cairo::Context::new(&surface);
+    fill_background(opt, img_view, &mut surface);
 
-    // Fill background.
-    if let Some(color) = opt.background {
-        cr.set_source_color(color, 1.0.into());
-        cr.paint();
-    }
+    let cr = cairo::Context::new(&surface);
 
     render_to_canvas(tree, opt, img_view, &cr);
 
@@ -159,14 +175,16 @@ pub fn render_node_to_image(
     node: &usvg::Node,
     opt: &Options,
 ) -> Option<cairo::ImageSurface> {
-    let node_bbox = if let Some(bbox) = node.calculate_bbox() {
+    let node_bbox = if let Some(bbox) = utils::node_visual_bbox(node) {
         bbox
     } else {
         warn!("Node '{}' has a zero size.", node.id());
         return None;
     };
 
-    let (surface, img_size) = create_surface(node_bbox.to_screen_size(), opt)?;
+    let (mut surface, img_size) = create_surface(node_bbox.to_screen_size(), opt)?;
+
+    fill_background(opt, img_size, &mut surface);
 
     let vbox = usvg::ViewBox {
         rect: node_bbox,
@@ -175,12 +193,6 @@ pub fn render_node_to_image(
 
     let cr = cairo::Context::new(&surface);
 
-    // Fill background.
-    if let Some(color) = opt.background {
-        cr.set_source_color(color, 1.0.into());
-        cr.paint();
-    }
-
     render_node_to_canvas(node, opt, vbox, img_size, &cr);
 
     Some(surface)
@@ -239,6 +251,29 @@ fn create_surface(
     Some((surface, img_size))
 }
 
+fn fill_background(
+    opt: &Options,
+    img_size: ScreenSize,
+    surface: &mut cairo::ImageSurface,
+) {
+    match opt.background {
+        Some(Background::Color(c)) => {
+            let cr = cairo::Context::new(surface);
+            cr.set_source_color(c, 1.0.into());
+            cr.paint();
+        }
+        Some(bg) => {
+            let bg = crate::image::prepare_background(bg, img_size);
+            // Unwrap is safe, because no one uses the surface.
+            let mut surface_data = surface.get_data().unwrap();
+            image::image_to_surface(&bg, &mut surface_data);
+        }
+        None => {
+            // A new image surface is already cleared to transparent by cairo.
+        }
+    }
+}
+
 /// Applies viewbox transformation to the painter.
 fn apply_viewbox_transform(
     view_box: usvg::ViewBox,
@@ -280,10 +315,16 @@ fn render_group(
     layers: &mut CairoLayers,
     cr: &cairo::Context,
 ) -> Option<Rect> {
+    let _depth = RenderDepthGuard::enter(opt)?;
+
     let curr_ts = cr.get_matrix();
     let mut g_bbox = Rect::new_bbox();
 
     for node in parent.children() {
+        if budget::is_exceeded() {
+            break;
+        }
+
         match state {
             RenderState::Ok => {}
             RenderState::RenderUntil(ref last) => {
@@ -363,9 +404,11 @@ fn render_group_impl(
                 let background = prepare_filter_background(node, filter, opt);
                 let fill_paint = prepare_filter_fill_paint(node, filter, bbox, ts, opt, &sub_surface);
                 let stroke_paint = prepare_filter_stroke_paint(node, filter, bbox, ts, opt, &sub_surface);
-                filter::apply(filter, bbox, &ts, opt, &node.tree(),
-                              background.as_ref(), fill_paint.as_ref(), stroke_paint.as_ref(),
-                              &mut *sub_surface);
+                perf::measure(perf::Phase::Filter, || {
+                    filter::apply(filter, bbox, &ts, opt, &node.tree(),
+                                  background.as_ref(), fill_paint.as_ref(), stroke_paint.as_ref(),
+                                  &mut *sub_surface);
+                });
             }
         }
     }
@@ -378,7 +421,9 @@ fn render_group_impl(
                     let sub_cr = cairo::Context::new(&*sub_surface);
                     sub_cr.set_matrix(curr_ts);
 
-                    clip_and_mask::clip(&clip_node, cp, opt, bbox, layers, &sub_cr);
+                    perf::measure(perf::Phase::ClipPath, || {
+                        clip_and_mask::clip(&clip_node, cp, opt, bbox, layers, &sub_cr)
+                    });
                 }
             }
         }
@@ -389,7 +434,9 @@ fn render_group_impl(
                     let sub_cr = cairo::Context::new(&*sub_surface);
                     sub_cr.set_matrix(curr_ts);
 
-                    clip_and_mask::mask(&mask_node, mask, opt, bbox, layers, &sub_cr);
+                    perf::measure(perf::Phase::Mask, || {
+                        clip_and_mask::mask(&mask_node, mask, opt, bbox, layers, &sub_cr)
+                    });
                 }
             }
         }
@@ -7,7 +7,6 @@ use std::rc::Rc;
 
 use crate::qt;
 use rgb::FromSlice;
-use log::warn;
 use usvg::ColorInterpolation as ColorSpace;
 
 use crate::prelude::*;
@@ -64,7 +64,7 @@ pub fn draw_raster(
     p.reset_clip_path();
 }
 
-fn image_to_surface(image: &image::Image, surface: &mut [u8]) {
+pub fn image_to_surface(image: &image::Image, surface: &mut [u8]) {
     // Surface is always ARGB.
     const SURFACE_CHANNELS: usize = 4;
 
@@ -5,9 +5,8 @@
 //! Qt backend implementation.
 
 use crate::qt;
-use log::warn;
 
-use crate::{prelude::*, layers, ConvTransform, RenderState};
+use crate::{prelude::*, budget, layers, perf, ConvTransform, RenderDepthGuard, RenderState};
 
 
 macro_rules! try_create_image {
@@ -53,7 +52,7 @@ impl Render for Backend {
         tree: &usvg::Tree,
         opt: &Options,
     ) -> Option<Box<dyn OutputImage>> {
-        let img = render_to_image(tree, opt)?;
+        let img = perf::trace_render(&tree.root(), || render_to_image(tree, opt))?;
         Some(Box::new(img))
     }
 
@@ -62,12 +61,16 @@ impl Render for Backend {
         node: &usvg::Node,
         opt: &Options,
     ) -> Option<Box<dyn OutputImage>> {
-        let img = render_node_to_image(node, opt)?;
+        let img = perf::trace_render(node, || render_node_to_image(node, opt))?;
         Some(Box::new(img))
     }
 }
 
 impl OutputImage for qt::Image {
+    fn size(&self) -> ScreenSize {
+        ScreenSize::new(self.width(), self.height()).unwrap()
+    }
+
     fn save_png(
         &mut self,
         path: &std::path::Path,
@@ -92,6 +95,24 @@ impl OutputImage for qt::Image {
 
         data
     }
+
+    fn make_rgba_vec_with_format(&mut self, format: PixelFormat) -> Vec<u8> {
+        use rgb::FromSlice;
+        use std::mem::swap;
+
+        match format {
+            PixelFormat::RgbaStraight => self.make_rgba_vec(),
+            PixelFormat::RgbaPremultiplied => {
+                // Return the internal data as is, without a lossy demultiplication.
+                let mut data = self.make_vec();
+
+                // BGRA_Premultiplied -> RGBA_Premultiplied.
+                data.as_bgra_mut().iter_mut().for_each(|p| swap(&mut p.r, &mut p.b));
+
+                data
+            }
+        }
+    }
 }
 
 /// Renders SVG to image.
@@ -113,7 +134,7 @@ pub fn render_node_to_image(
     node: &usvg::Node,
     opt: &Options,
 ) -> Option<qt::Image> {
-    let node_bbox = if let Some(bbox) = node.calculate_bbox() {
+    let node_bbox = if let Some(bbox) = utils::node_visual_bbox(node) {
         bbox
     } else {
         warn!("Node '{}' has zero size.", node.id());
@@ -186,10 +207,17 @@ fn create_root_image(
     let mut img = try_create_image!(img_size, None);
 
     // Fill background.
-    if let Some(c) = opt.background {
-        img.fill(c.red, c.green, c.blue, 255);
-    } else {
-        img.fill(0, 0, 0, 0);
+    match opt.background {
+        Some(Background::Color(c)) => {
+            img.fill(c.red, c.green, c.blue, 255);
+        }
+        Some(bg) => {
+            let bg = crate::image::prepare_background(bg, img_size);
+            image::image_to_surface(&bg, &mut img.data_mut());
+        }
+        None => {
+            img.fill(0, 0, 0, 0);
+        }
     }
 
     Some((img, img_size))
@@ -236,10 +264,16 @@ fn render_group(
     layers: &mut QtLayers,
     p: &mut qt::Painter,
 ) -> Option<Rect> {
+    let _depth = RenderDepthGuard::enter(opt)?;
+
     let curr_ts = p.get_transform();
     let mut g_bbox = Rect::new_bbox();
 
     for node in parent.children() {
+        if budget::is_exceeded() {
+            break;
+        }
+
         match state {
             RenderState::Ok => {}
             RenderState::RenderUntil(ref last) => {
@@ -317,9 +351,11 @@ fn render_group_impl(
                 let background = prepare_filter_background(node, filter, opt);
                 let fill_paint = prepare_filter_fill_paint(node, filter, bbox, ts, opt, &sub_img);
                 let stroke_paint = prepare_filter_stroke_paint(node, filter, bbox, ts, opt, &sub_img);
-                filter::apply(filter, bbox, &ts, opt, &node.tree(),
-                              background.as_ref(), fill_paint.as_ref(), stroke_paint.as_ref(),
-                              &mut sub_img);
+                perf::measure(perf::Phase::Filter, || {
+                    filter::apply(filter, bbox, &ts, opt, &node.tree(),
+                                  background.as_ref(), fill_paint.as_ref(), stroke_paint.as_ref(),
+                                  &mut sub_img);
+                });
             }
         }
     }
@@ -332,7 +368,9 @@ fn render_group_impl(
                     let mut sub_p = qt::Painter::new(&mut sub_img);
                     sub_p.set_transform(&curr_ts);
 
-                    clip_and_mask::clip(&clip_node, cp, opt, bbox, layers, &mut sub_p);
+                    perf::measure(perf::Phase::ClipPath, || {
+                        clip_and_mask::clip(&clip_node, cp, opt, bbox, layers, &mut sub_p)
+                    });
                 }
             }
         }
@@ -343,7 +381,9 @@ fn render_group_impl(
                     let mut sub_p = qt::Painter::new(&mut sub_img);
                     sub_p.set_transform(&curr_ts);
 
-                    clip_and_mask::mask(&mask_node, mask, opt, bbox, layers, &mut sub_p);
+                    perf::measure(perf::Phase::Mask, || {
+                        clip_and_mask::mask(&mask_node, mask, opt, bbox, layers, &mut sub_p)
+                    });
                 }
             }
         }
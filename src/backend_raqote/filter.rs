@@ -6,7 +6,6 @@ use std::cmp;
 use std::rc::Rc;
 
 use rgb::FromSlice;
-use log::warn;
 
 use usvg::ColorInterpolation as ColorSpace;
 
@@ -370,10 +369,12 @@ impl Filter<raqote::DrawTarget> for RaqoteFilter {
 
         let ts: raqote::Transform = brush_ts.to_native();
         let ts = ts.inverse().unwrap();
+        // The tile is shifted by whole pixels, so there is nothing to interpolate.
+        // A bilinear filter would only lose precision and make opaque pixels translucent.
         let patt = raqote::Source::Image(
             tile.as_image(),
             raqote::ExtendMode::Repeat,
-            raqote::FilterMode::Bilinear,
+            raqote::FilterMode::Nearest,
             ts,
         );
 
@@ -4,9 +4,7 @@
 
 //! Raqote backend implementation.
 
-use log::warn;
-
-use crate::{prelude::*, layers, ConvTransform, RenderState};
+use crate::{prelude::*, budget, layers, perf, ConvTransform, RenderDepthGuard, RenderState};
 
 mod clip_and_mask;
 mod filter;
@@ -94,7 +92,7 @@ impl Render for Backend {
         tree: &usvg::Tree,
         opt: &Options,
     ) -> Option<Box<dyn OutputImage>> {
-        let img = render_to_image(tree, opt)?;
+        let img = perf::trace_render(&tree.root(), || render_to_image(tree, opt))?;
         Some(Box::new(img))
     }
 
@@ -103,12 +101,16 @@ impl Render for Backend {
         node: &usvg::Node,
         opt: &Options,
     ) -> Option<Box<dyn OutputImage>> {
-        let img = render_node_to_image(node, opt)?;
+        let img = perf::trace_render(node, || render_node_to_image(node, opt))?;
         Some(Box::new(img))
     }
 }
 
 impl OutputImage for raqote::DrawTarget {
+    fn size(&self) -> ScreenSize {
+        ScreenSize::new(self.width() as u32, self.height() as u32).unwrap()
+    }
+
     fn save_png(&mut self, path: &::std::path::Path) -> bool {
         self.write_png(path).is_ok()
     }
@@ -130,6 +132,24 @@ impl OutputImage for raqote::DrawTarget {
 
         data
     }
+
+    fn make_rgba_vec_with_format(&mut self, format: PixelFormat) -> Vec<u8> {
+        use rgb::FromSlice;
+        use std::mem::swap;
+
+        match format {
+            PixelFormat::RgbaStraight => self.make_rgba_vec(),
+            PixelFormat::RgbaPremultiplied => {
+                // Return the internal data as is, without a lossy demultiplication.
+                let mut data = self.make_vec();
+
+                // BGRA_Premultiplied -> RGBA_Premultiplied.
+                data.as_bgra_mut().iter_mut().for_each(|p| swap(&mut p.r, &mut p.b));
+
+                data
+            }
+        }
+    }
 }
 
 
@@ -143,10 +163,7 @@ pub fn render_to_image(
         opt,
     )?;
 
-    // Fill background.
-    if let Some(c) = opt.background {
-        dt.clear(raqote::SolidSource { r: c.red, g: c.green, b: c.blue, a: 255 });
-    }
+    fill_background(opt, img_view, &mut dt);
 
     render_to_canvas(tree, opt, img_view, &mut dt);
 
@@ -158,7 +175,7 @@ pub fn render_node_to_image(
     node: &usvg::Node,
     opt: &Options,
 ) -> Option<raqote::DrawTarget> {
-    let node_bbox = if let Some(bbox) = node.calculate_bbox() {
+    let node_bbox = if let Some(bbox) = utils::node_visual_bbox(node) {
         bbox
     } else {
         warn!("Node '{}' has a zero size.", node.id());
@@ -167,16 +184,13 @@ pub fn render_node_to_image(
 
     let (mut dt, img_size) = create_target(node_bbox.to_screen_size(), opt)?;
 
+    fill_background(opt, img_size, &mut dt);
+
     let vbox = usvg::ViewBox {
         rect: node_bbox,
         aspect: usvg::AspectRatio::default(),
     };
 
-    // Fill background.
-    if let Some(c) = opt.background {
-        dt.clear(raqote::SolidSource { r: c.red, g: c.green, b: c.blue, a: 255 });
-    }
-
     render_node_to_canvas(node, opt, vbox, img_size, &mut dt);
 
     Some(dt)
@@ -235,6 +249,25 @@ fn create_target(
     Some((dt, img_size))
 }
 
+fn fill_background(
+    opt: &Options,
+    img_size: ScreenSize,
+    dt: &mut raqote::DrawTarget,
+) {
+    match opt.background {
+        Some(Background::Color(c)) => {
+            dt.clear(raqote::SolidSource { r: c.red, g: c.green, b: c.blue, a: 255 });
+        }
+        Some(bg) => {
+            let bg = crate::image::prepare_background(bg, img_size);
+            image::image_to_surface(&bg, dt.get_data_u8_mut());
+        }
+        None => {
+            dt.clear(raqote::SolidSource { r: 0, g: 0, b: 0, a: 0 });
+        }
+    }
+}
+
 /// Applies viewbox transformation to the painter.
 fn apply_viewbox_transform(
     view_box: usvg::ViewBox,
@@ -276,10 +309,16 @@ fn render_group(
     layers: &mut RaqoteLayers,
     dt: &mut raqote::DrawTarget,
 ) -> Option<Rect> {
+    let _depth = RenderDepthGuard::enter(opt)?;
+
     let curr_ts = *dt.get_transform();
     let mut g_bbox = Rect::new_bbox();
 
     for node in parent.children() {
+        if budget::is_exceeded() {
+            break;
+        }
+
         match state {
             RenderState::Ok => {}
             RenderState::RenderUntil(ref last) => {
@@ -355,9 +394,11 @@ fn render_group_impl(
                 let background = prepare_filter_background(node, filter, opt);
                 let fill_paint = prepare_filter_fill_paint(node, filter, bbox, ts, opt, &sub_dt);
                 let stroke_paint = prepare_filter_stroke_paint(node, filter, bbox, ts, opt, &sub_dt);
-                filter::apply(filter, bbox, &ts, opt, &node.tree(),
-                              background.as_ref(), fill_paint.as_ref(), stroke_paint.as_ref(),
-                              &mut sub_dt);
+                perf::measure(perf::Phase::Filter, || {
+                    filter::apply(filter, bbox, &ts, opt, &node.tree(),
+                                  background.as_ref(), fill_paint.as_ref(), stroke_paint.as_ref(),
+                                  &mut sub_dt);
+                });
             }
         }
     }
@@ -369,7 +410,9 @@ fn render_group_impl(
                 if let usvg::NodeKind::ClipPath(ref cp) = *clip_node.borrow() {
                     sub_dt.set_transform(&curr_ts);
 
-                    clip_and_mask::clip(&clip_node, cp, opt, bbox, layers, &mut sub_dt);
+                    perf::measure(perf::Phase::ClipPath, || {
+                        clip_and_mask::clip(&clip_node, cp, opt, bbox, layers, &mut sub_dt)
+                    });
                 }
             }
         }
@@ -379,7 +422,9 @@ fn render_group_impl(
                 if let usvg::NodeKind::Mask(ref mask) = *mask_node.borrow() {
                     sub_dt.set_transform(&curr_ts);
 
-                    clip_and_mask::mask(&mask_node, mask, opt, bbox, layers, &mut sub_dt);
+                    perf::measure(perf::Phase::Mask, || {
+                        clip_and_mask::mask(&mask_node, mask, opt, bbox, layers, &mut sub_dt)
+                    });
                 }
             }
         }
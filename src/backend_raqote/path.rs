@@ -42,8 +42,16 @@ pub fn draw(
         draw_opt.antialias = raqote::AntialiasMode::None;
     }
 
-    style::fill(tree, &new_path, &path.fill, opt, style_bbox, &draw_opt, dt);
-    style::stroke(tree, &new_path, &path.stroke, opt, style_bbox, &draw_opt, dt);
+    match path.paint_order {
+        usvg::PaintOrder::FillAndStroke => {
+            style::fill(tree, &new_path, &path.fill, opt, style_bbox, &draw_opt, dt);
+            style::stroke(tree, &new_path, &path.stroke, opt, style_bbox, &draw_opt, dt);
+        }
+        usvg::PaintOrder::StrokeAndFill => {
+            style::stroke(tree, &new_path, &path.stroke, opt, style_bbox, &draw_opt, dt);
+            style::fill(tree, &new_path, &path.fill, opt, style_bbox, &draw_opt, dt);
+        }
+    }
 
     bbox
 }
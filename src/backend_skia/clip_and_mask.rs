@@ -140,11 +140,21 @@ pub fn mask(
         mask_surface.restore();
     }
 
+    // A luminance must be calculated in sRGB, so a linear RGB mask has to be converted first.
+    // A mask has only an alpha channel after that, which doesn't depend on a color space.
+    let mut srgb_mask;
+    let mask_img: &mut skia::Surface = if opt.linear_compositing {
+        srgb_mask = try_opt!(mask_surface.copy_to_srgb());
+        &mut srgb_mask
+    } else {
+        &mut *mask_surface
+    };
+
     {
         use rgb::FromSlice;
         use std::mem::swap;
 
-        let mut data = mask_surface.data_mut();
+        let mut data = mask_img.data_mut();
 
         // RGBA -> BGRA.
         if !skia::Surface::is_bgra() {
@@ -169,6 +179,6 @@ pub fn mask(
 
     canvas.reset_matrix();
     canvas.draw_surface(
-        &mask_surface, 0.0, 0.0, 255, skia::BlendMode::DestinationIn, skia::FilterQuality::Low,
+        mask_img, 0.0, 0.0, 255, skia::BlendMode::DestinationIn, skia::FilterQuality::Low,
     );
 }
@@ -7,7 +7,6 @@ use std::rc::Rc;
 
 use crate::skia;
 use rgb::FromSlice;
-use log::warn;
 use usvg::ColorInterpolation as ColorSpace;
 
 use crate::prelude::*;
@@ -392,7 +391,7 @@ impl Filter<skia::Surface> for SkiaFilter {
             }
             usvg::FeImageKind::Use(ref id) => {
                 if let Some(ref node) = tree.defs_by_id(id).or(tree.node_by_id(id)) {
-                    let mut layers = super::create_layers(region.size());
+                    let mut layers = super::create_layers(region.size(), opt);
 
                     let (sx, sy) = ts.get_scale();
                     buffer.scale(sx, sy);
@@ -68,7 +68,7 @@ pub fn draw_raster(
     canvas.restore();
 }
 
-fn image_to_surface(image: &image::Image, surface: &mut [u8]) {
+pub fn image_to_surface(image: &image::Image, surface: &mut [u8]) {
     // Surface is always ARGB.
     const SURFACE_CHANNELS: usize = 4;
 
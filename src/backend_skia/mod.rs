@@ -4,10 +4,13 @@
 
 //! Skia backend implementation.
 
+use std::cell::RefCell;
+use std::cmp;
+use std::rc::Rc;
+
 use crate::skia;
-use log::warn;
 
-use crate::{prelude::*, layers, ConvTransform, RenderState};
+use crate::{prelude::*, budget, layers, perf, ConvTransform, RenderDepthGuard, RenderState};
 
 macro_rules! try_create_surface {
     ($size:expr, $ret:expr) => {
@@ -49,7 +52,7 @@ impl Render for Backend {
         tree: &usvg::Tree,
         opt: &Options,
     ) -> Option<Box<dyn OutputImage>> {
-        let img = render_to_image(tree, opt)?;
+        let img = perf::trace_render(&tree.root(), || render_to_image(tree, opt))?;
         Some(Box::new(img))
     }
 
@@ -58,12 +61,16 @@ impl Render for Backend {
         node: &usvg::Node,
         opt: &Options,
     ) -> Option<Box<dyn OutputImage>> {
-        let img = render_node_to_image(node, opt)?;
+        let img = perf::trace_render(node, || render_node_to_image(node, opt))?;
         Some(Box::new(img))
     }
 }
 
 impl OutputImage for skia::Surface {
+    fn size(&self) -> ScreenSize {
+        ScreenSize::new(self.width(), self.height()).unwrap()
+    }
+
     fn save_png(
         &mut self,
         path: &std::path::Path,
@@ -90,6 +97,26 @@ impl OutputImage for skia::Surface {
 
         data
     }
+
+    fn make_rgba_vec_with_format(&mut self, format: PixelFormat) -> Vec<u8> {
+        use rgb::FromSlice;
+        use std::mem::swap;
+
+        match format {
+            PixelFormat::RgbaStraight => self.make_rgba_vec(),
+            PixelFormat::RgbaPremultiplied => {
+                // Return the internal data as is, without a lossy demultiplication.
+                let mut data = self.make_vec();
+
+                // BGRA_Premultiplied -> RGBA_Premultiplied.
+                if skia::Surface::is_bgra() {
+                    data.as_bgra_mut().iter_mut().for_each(|p| swap(&mut p.r, &mut p.b));
+                }
+
+                data
+            }
+        }
+    }
 }
 
 /// Renders SVG to image.
@@ -99,15 +126,308 @@ pub fn render_to_image(
 ) -> Option<skia::Surface> {
     let (mut img, img_size) = create_root_image(tree.svg_node().size.to_screen_size(), opt)?;
     render_to_canvas(tree, opt, img_size, &mut img);
+    finish_image(img, opt)
+}
+
+/// A renderer that reuses intermediate layers between renders.
+///
+/// Layer buffers are allocated on demand and are kept for the largest image rendered so far.
+/// Smaller images are rendered into the top-left part of these buffers,
+/// so rendering many images of different sizes will not reallocate them.
+/// Useful for servers rendering many images in a row.
+///
+/// Available only in the Skia backend.
+///
+/// `Renderer` is single-threaded. Use one per thread.
+pub struct Renderer {
+    // Layers are drawing into `buffers`, so they must be dropped first.
+    layers: Option<SkiaLayers>,
+    buffers: Rc<RefCell<LayerBuffers>>,
+    linear_compositing: bool,
+}
+
+impl Renderer {
+    /// Creates a new `Renderer`.
+    pub fn new() -> Self {
+        Renderer {
+            layers: None,
+            buffers: Rc::new(RefCell::new(LayerBuffers { size: (0, 0), list: Vec::new() })),
+            linear_compositing: false,
+        }
+    }
+
+    /// Renders SVG to image.
+    ///
+    /// Produces the same result as `render_to_image`.
+    pub fn render(
+        &mut self,
+        tree: &usvg::Tree,
+        opt: &Options,
+    ) -> Option<skia::Surface> {
+        let (mut img, img_size) = create_root_image(tree.svg_node().size.to_screen_size(), opt)?;
+        let layers = self.layers(img_size, opt);
+        render_node_to_canvas_impl(
+            &tree.root(), opt, tree.svg_node().view_box, img_size,
+            &mut RenderState::Ok, layers, &mut img,
+        );
+        finish_image(img, opt)
+    }
+
+    /// Returns the size of the allocated layer buffers.
+    ///
+    /// Equals to the largest width and height rendered so far.
+    pub fn buffer_size(&self) -> (u32, u32) {
+        self.buffers.borrow().size
+    }
+
+    fn layers(
+        &mut self,
+        img_size: ScreenSize,
+        opt: &Options,
+    ) -> &mut SkiaLayers {
+        let is_compatible = match self.layers {
+            Some(ref layers) => {
+                layers.image_size() == img_size && self.linear_compositing == opt.linear_compositing
+            }
+            None => false,
+        };
+
+        if !is_compatible {
+            self.layers = None;
+            self.buffers.borrow_mut().fit(img_size);
+            let buffers = self.buffers.clone();
+            self.layers = Some(create_buffered_layers(img_size, opt.linear_compositing, buffers));
+            self.linear_compositing = opt.linear_compositing;
+        }
+
+        self.layers.as_mut().unwrap()
+    }
+}
+
+/// Premultiplied RGBA buffers shared by `Renderer` layers.
+struct LayerBuffers {
+    size: (u32, u32),
+    list: Vec<Box<[u8]>>,
+}
+
+impl LayerBuffers {
+    /// Grows buffers to fit an image with the specified size.
+    ///
+    /// Must not be called while any layer is alive, since grown buffers will be reallocated.
+    fn fit(&mut self, img_size: ScreenSize) {
+        let size = (
+            cmp::max(self.size.0, img_size.width()),
+            cmp::max(self.size.1, img_size.height()),
+        );
+
+        if size != self.size {
+            self.size = size;
+            self.list.clear();
+        }
+    }
+}
+
+/// Creates layers that are drawing into the top-left part of the shared `buffers`.
+fn create_buffered_layers(
+    img_size: ScreenSize,
+    linear_compositing: bool,
+    buffers: Rc<RefCell<LayerBuffers>>,
+) -> SkiaLayers {
+    // Layers are allocated one by one, so the n-th layer always uses the n-th buffer.
+    let mut idx = 0;
+    let new_img_fn = move |size: ScreenSize| {
+        let mut buffers = buffers.borrow_mut();
+        let stride = buffers.size.0 as usize * 4;
+
+        let is_new = idx == buffers.list.len();
+        if is_new {
+            let len = stride * buffers.size.1 as usize;
+            buffers.list.push(vec![0; len].into_boxed_slice());
+        }
+
+        // Buffers are not reallocated or dropped while layers are alive,
+        // since `Renderer` drops layers first and `buffers` are also owned by `Layers`.
+        let data = &mut buffers.list[idx];
+        let img = unsafe {
+            skia::Surface::wrap_pixels_unchecked(
+                size.width(), size.height(), stride, data, linear_compositing,
+            )
+        };
+        let mut img = try_opt_warn_or!(
+            img, None, "Failed to create a {}x{} surface.", size.width(), size.height()
+        );
+
+        idx += 1;
+
+        // A reused buffer contains a previous image.
+        if !is_new {
+            clear_image(&mut img);
+        }
+
+        Some(img)
+    };
+
+    layers::Layers::new(img_size, new_img_fn, clear_image)
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer::new()
+    }
+}
+
+/// Renders a region of an SVG image.
+///
+/// `dirty` is set in the document coordinates, i.e. in the same units as elements
+/// bounding boxes, and will be converted to the output image coordinates
+/// using the `viewBox` transform and `Options::fit_to`. The resulting rect is rounded
+/// outwards to whole pixels and clipped by the image bounds.
+///
+/// The result contains the same pixels as the corresponding region of the `render_to_image`
+/// output, so it can be drawn over a previous full render at the region position.
+/// Use `region_to_screen_rect` to get this position.
+/// Note that antialiasing affects pixels just outside the shapes, therefore a `dirty` rect
+/// calculated from the bounding boxes of the changed elements should be expanded
+/// by at least one pixel in the output image units, in addition to a stroke width.
+///
+/// Since a filter can affect pixels far away from an element,
+/// documents with filters are rendered in full and then cropped.
+pub fn render_region(
+    tree: &usvg::Tree,
+    opt: &Options,
+    dirty: Rect,
+) -> Option<skia::Surface> {
+    let region = region_to_screen_rect(tree, opt, dirty)?;
+    render_screen_region(tree, opt, region)
+}
+
+/// Converts a `render_region` rect from the document coordinates
+/// to the output image coordinates.
+///
+/// Returns `None` when the rect is outside the image.
+pub fn region_to_screen_rect(
+    tree: &usvg::Tree,
+    opt: &Options,
+    dirty: Rect,
+) -> Option<ScreenRect> {
+    let img_size = utils::fit_to(tree.svg_node().size.to_screen_size(), opt.fit_to)?;
+    let view_box = tree.svg_node().view_box;
+    let ts = utils::view_box_to_transform(view_box.rect, view_box.aspect, img_size.to_size());
+    let rect = dirty.transform(&ts)?;
+
+    // Round outwards and clip by the image bounds.
+    let x = rect.x().floor() as i32;
+    let y = rect.y().floor() as i32;
+    let w = (rect.right().ceil() as i32 - x) as u32;
+    let h = (rect.bottom().ceil() as i32 - y) as u32;
+    clip_by_image(ScreenRect::new(x, y, w, h)?, img_size)
+}
+
+/// Returns an intersection of the `rect` and the image bounds.
+///
+/// Returns `None` when the `rect` is outside the image.
+fn clip_by_image(
+    rect: ScreenRect,
+    img_size: ScreenSize,
+) -> Option<ScreenRect> {
+    let x1 = cmp::max(rect.x(), 0);
+    let y1 = cmp::max(rect.y(), 0);
+    let x2 = cmp::min(rect.right(), img_size.width() as i32);
+    let y2 = cmp::min(rect.bottom(), img_size.height() as i32);
+    if x2 <= x1 || y2 <= y1 {
+        return None;
+    }
+
+    ScreenRect::new(x1, y1, (x2 - x1) as u32, (y2 - y1) as u32)
+}
+
+fn render_screen_region(
+    tree: &usvg::Tree,
+    opt: &Options,
+    region: ScreenRect,
+) -> Option<skia::Surface> {
+    let img_size = utils::fit_to(tree.svg_node().size.to_screen_size(), opt.fit_to)?;
+    let region = clip_by_image(region, img_size)?;
+
+    // Render in a higher resolution when supersampling is enabled.
+    let factor = opt.supersample_factor();
+    let img_size = ScreenSize::new(img_size.width() * factor, img_size.height() * factor)?;
+    let region = ScreenRect::new(
+        region.x() * factor as i32, region.y() * factor as i32,
+        region.width() * factor, region.height() * factor,
+    )?;
+
+    if has_filters(tree) {
+        let full = render_to_image(tree, opt)?;
+        let (x, y) = (region.x() / factor as i32, region.y() / factor as i32);
+        let size = ScreenSize::new(region.width() / factor, region.height() / factor)?;
+        let mut img = try_create_surface!(size, None);
+        img.fill(0, 0, 0, 0);
+        img.draw_surface(
+            &full, -x as f64, -y as f64, 255,
+            skia::BlendMode::SourceOver, skia::FilterQuality::None,
+        );
+        return Some(img);
+    }
+
+    let mut img = create_surface(region.size(), opt)?;
+    fill_background(opt, img_size, region, &mut img);
+
+    // Layers must have the region size, but the viewbox transform is still based
+    // on the full image size.
+    let mut layers = create_layers(region.size(), opt);
+
+    img.translate(-region.x() as f64, -region.y() as f64);
+    apply_viewbox_transform(tree.svg_node().view_box, img_size, &mut img);
+    render_node(&tree.root(), opt, &mut RenderState::Ok, &mut layers, &mut img);
+
+    finish_image(img, opt)
+}
+
+/// Renders SVG to image, but only inside the `clip` rect.
+///
+/// Unlike `render_region`, the output image has a full size,
+/// and pixels outside the `clip` rect are transparent.
+/// `clip` is set in the output image coordinates.
+/// When `clip` is outside the image, a fully transparent image will be returned.
+pub fn render_clipped(
+    tree: &usvg::Tree,
+    opt: &Options,
+    clip: ScreenRect,
+) -> Option<skia::Surface> {
+    let img_size = utils::fit_to(tree.svg_node().size.to_screen_size(), opt.fit_to)?;
+    let mut img = create_subimage(img_size)?;
+
+    let clip = match clip_by_image(clip, img_size) {
+        Some(clip) => clip,
+        None => return Some(img),
+    };
+
+    let region_img = render_screen_region(tree, opt, clip)?;
+    img.draw_surface(
+        &region_img, clip.x() as f64, clip.y() as f64, 255,
+        skia::BlendMode::SourceOver, skia::FilterQuality::None,
+    );
+
     Some(img)
 }
 
+fn has_filters(tree: &usvg::Tree) -> bool {
+    tree.root().descendants().any(|n| {
+        if let usvg::NodeKind::Group(ref g) = *n.borrow() {
+            g.filter.is_some()
+        } else {
+            false
+        }
+    })
+}
+
 /// Renders SVG node to image.
 pub fn render_node_to_image(
     node: &usvg::Node,
     opt: &Options,
 ) -> Option<skia::Surface> {
-    let node_bbox = if let Some(bbox) = node.calculate_bbox() {
+    let node_bbox = if let Some(bbox) = utils::node_visual_bbox(node) {
         bbox
     } else {
         warn!("Node '{}' has zero size.", node.id());
@@ -122,7 +442,7 @@ pub fn render_node_to_image(
     let (mut img, img_size) = create_root_image(node_bbox.size().to_screen_size(), opt)?;
 
     render_node_to_canvas(node, opt, vbox, img_size, &mut img);
-    Some(img)
+    finish_image(img, opt)
 }
 
 /// Renders SVG to canvas.
@@ -143,7 +463,9 @@ pub fn render_node_to_canvas(
     img_size: ScreenSize,
     canvas: &mut skia::Canvas,
 ) {
-    render_node_to_canvas_impl(node, opt, view_box, img_size, &mut RenderState::Ok, canvas)
+    let mut layers = create_layers(img_size, opt);
+    let mut state = RenderState::Ok;
+    render_node_to_canvas_impl(node, opt, view_box, img_size, &mut state, &mut layers, canvas)
 }
 
 fn render_node_to_canvas_impl(
@@ -152,10 +474,9 @@ fn render_node_to_canvas_impl(
     view_box: usvg::ViewBox,
     img_size: ScreenSize,
     state: &mut RenderState,
+    layers: &mut SkiaLayers,
     canvas: &mut skia::Canvas,
 ) {
-    let mut layers = create_layers(img_size);
-
     apply_viewbox_transform(view_box, img_size, canvas);
 
     let curr_ts = canvas.get_matrix();
@@ -164,7 +485,7 @@ fn render_node_to_canvas_impl(
     ts.append(&node.transform());
 
     canvas.concat(&ts.to_native());
-    render_node(node, opt, state, &mut layers, canvas);
+    render_node(node, opt, state, layers, canvas);
     canvas.set_matrix(&curr_ts);
 }
 
@@ -174,16 +495,83 @@ fn create_root_image(
 ) -> Option<(skia::Surface, ScreenSize)> {
     let img_size = utils::fit_to(size, opt.fit_to)?;
 
-    let mut img = try_create_surface!(img_size, None);
+    // Render in a higher resolution when supersampling is enabled.
+    let factor = opt.supersample_factor();
+    let img_size = ScreenSize::new(img_size.width() * factor, img_size.height() * factor)?;
+
+    let mut img = create_surface(img_size, opt)?;
+    let region = ScreenRect::new(0, 0, img_size.width(), img_size.height())?;
+    fill_background(opt, img_size, region, &mut img);
 
-    // Fill background.
-    if let Some(c) = opt.background {
-        img.fill(c.red, c.green, c.blue, 255);
+    Some((img, img_size))
+}
+
+/// Creates a surface for the root image or a layer.
+///
+/// The surface has a linear RGB color space when `Options::linear_compositing` is set.
+fn create_surface(
+    size: ScreenSize,
+    opt: &Options,
+) -> Option<skia::Surface> {
+    if opt.linear_compositing {
+        create_linear_subimage(size)
     } else {
-        img.fill(0, 0, 0, 0);
+        create_subimage(size)
     }
+}
 
-    Some((img, img_size))
+/// Fills the `region` of an image with the specified `img_size` with a background.
+fn fill_background(
+    opt: &Options,
+    img_size: ScreenSize,
+    region: ScreenRect,
+    img: &mut skia::Surface,
+) {
+    match opt.background {
+        Some(Background::Color(c)) => {
+            img.fill(c.red, c.green, c.blue, 255);
+        }
+        Some(bg) => {
+            let bg = crate::image::prepare_background_region(bg, img_size, region);
+            if opt.linear_compositing {
+                // Background pixels are in sRGB, so we have to let Skia to convert them.
+                let mut bg_img = try_create_surface!(region.size(), ());
+                image::image_to_surface(&bg, &mut bg_img.data_mut());
+                img.draw_surface(
+                    &bg_img, 0.0, 0.0, 255, skia::BlendMode::SourceOver, skia::FilterQuality::None,
+                );
+            } else {
+                image::image_to_surface(&bg, &mut img.data_mut());
+            }
+        }
+        None => {
+            img.fill(0, 0, 0, 0);
+        }
+    }
+}
+
+/// Downscales a supersampled image and converts
+/// an image rendered with `Options::linear_compositing` back to sRGB.
+fn finish_image(
+    img: skia::Surface,
+    opt: &Options,
+) -> Option<skia::Surface> {
+    let factor = opt.supersample_factor();
+    let img = if factor > 1 {
+        let src_size = ScreenSize::new(img.width(), img.height())?;
+        let size = ScreenSize::new(img.width() / factor, img.height() / factor)?;
+        let mut small = create_surface(size, opt)?;
+        crate::image::downsample(&img.data(), src_size, factor, &mut small.data_mut());
+        small
+    } else {
+        img
+    };
+
+    if opt.linear_compositing {
+        img.copy_to_srgb()
+    } else {
+        Some(img)
+    }
 }
 
 /// Applies viewbox transformation to the painter.
@@ -227,10 +615,21 @@ fn render_group(
     layers: &mut SkiaLayers,
     canvas: &mut skia::Canvas,
 ) -> Option<Rect> {
+    let _depth = RenderDepthGuard::enter(opt)?;
+
     let curr_ts = canvas.get_matrix();
     let mut g_bbox = Rect::new_bbox();
 
+    let knockout = match *parent.borrow() {
+        usvg::NodeKind::Group(ref g) => g.knockout,
+        _ => false,
+    };
+
     for node in parent.children() {
+        if budget::is_exceeded() {
+            break;
+        }
+
         match state {
             RenderState::Ok => {}
             RenderState::RenderUntil(ref last) => {
@@ -245,7 +644,12 @@ fn render_group(
 
         canvas.concat(&node.transform().to_native());
 
-        let bbox = render_node(&node, opt, state, layers, canvas);
+        let bbox = if knockout {
+            render_knockout_child(&node, opt, state, layers, canvas)
+        } else {
+            render_node(&node, opt, state, layers, canvas)
+        };
+
         if let Some(bbox) = bbox {
             if let Some(bbox) = bbox.transform(&node.transform()) {
                 g_bbox = g_bbox.expand(bbox);
@@ -264,6 +668,69 @@ fn render_group(
     }
 }
 
+/// Renders a child of a knockout group.
+///
+/// The area covered by the child's shape is cleared before the child is added,
+/// so it's composited against the group's initial (transparent) backdrop
+/// and not against the previous siblings.
+///
+/// Only paths have a shape that differs from their alpha.
+/// For other nodes this is the same as a simple `SourceOver`.
+fn render_knockout_child(
+    node: &usvg::Node,
+    opt: &Options,
+    state: &mut RenderState,
+    layers: &mut SkiaLayers,
+    canvas: &mut skia::Canvas,
+) -> Option<Rect> {
+    let path = match *node.borrow() {
+        usvg::NodeKind::Path(ref path) => path.clone(),
+        _ => return render_node(node, opt, state, layers, canvas),
+    };
+
+    let shape_layer = layers.get()?;
+    let mut shape_layer = shape_layer.borrow_mut();
+
+    let child_layer = layers.get()?;
+    let mut child_layer = child_layer.borrow_mut();
+
+    let tree = node.tree();
+    let curr_ts = canvas.get_matrix();
+
+    shape_layer.set_matrix(&curr_ts);
+    path::draw(&tree, &opaque_shape(path.clone()), opt, skia::BlendMode::SourceOver,
+               &mut shape_layer);
+
+    child_layer.set_matrix(&curr_ts);
+    let bbox = path::draw(&tree, &path, opt, skia::BlendMode::SourceOver, &mut child_layer);
+
+    canvas.reset_matrix();
+    canvas.draw_surface(
+        &shape_layer, 0.0, 0.0, 255, skia::BlendMode::DestinationOut, skia::FilterQuality::Low,
+    );
+    canvas.draw_surface(
+        &child_layer, 0.0, 0.0, 255, skia::BlendMode::Plus, skia::FilterQuality::Low,
+    );
+    canvas.set_matrix(&curr_ts);
+
+    bbox
+}
+
+/// Replaces path's fill and stroke with an opaque black color.
+fn opaque_shape(mut path: usvg::Path) -> usvg::Path {
+    if let Some(ref mut fill) = path.fill {
+        fill.paint = usvg::Paint::Color(usvg::Color::black());
+        fill.opacity = usvg::Opacity::default();
+    }
+
+    if let Some(ref mut stroke) = path.stroke {
+        stroke.paint = usvg::Paint::Color(usvg::Color::black());
+        stroke.opacity = usvg::Opacity::default();
+    }
+
+    path
+}
+
 fn render_group_impl(
     node: &usvg::Node,
     g: &usvg::Group,
@@ -305,12 +772,14 @@ fn render_group_impl(
         if let Some(filter_node) = node.tree().defs_by_id(id) {
             if let usvg::NodeKind::Filter(ref filter) = *filter_node.borrow() {
                 let ts = usvg::Transform::from_native(&curr_ts);
-                let background = prepare_filter_background(node, filter, opt);
+                let background = prepare_filter_background(node, filter, layers.image_size(), opt);
                 let fill_paint = prepare_filter_fill_paint(node, filter, bbox, ts, opt, &sub_surface);
                 let stroke_paint = prepare_filter_stroke_paint(node, filter, bbox, ts, opt, &sub_surface);
-                filter::apply(filter, bbox, &ts, opt, &node.tree(),
-                              background.as_ref(), fill_paint.as_ref(), stroke_paint.as_ref(),
-                              &mut sub_surface);
+                perf::measure(perf::Phase::Filter, || {
+                    filter::apply(filter, bbox, &ts, opt, &node.tree(),
+                                  background.as_ref(), fill_paint.as_ref(), stroke_paint.as_ref(),
+                                  &mut sub_surface);
+                });
             }
         }
     }
@@ -321,7 +790,9 @@ fn render_group_impl(
             if let Some(clip_node) = node.tree().defs_by_id(id) {
                 if let usvg::NodeKind::ClipPath(ref cp) = *clip_node.borrow() {
                     sub_surface.set_matrix(&curr_ts);
-                    clip_and_mask::clip(&clip_node, cp, opt, bbox, layers, &mut sub_surface);
+                    perf::measure(perf::Phase::ClipPath, || {
+                        clip_and_mask::clip(&clip_node, cp, opt, bbox, layers, &mut sub_surface)
+                    });
                 }
             }
         }
@@ -330,7 +801,9 @@ fn render_group_impl(
             if let Some(mask_node) = node.tree().defs_by_id(id) {
                 if let usvg::NodeKind::Mask(ref mask) = *mask_node.borrow() {
                     sub_surface.set_matrix(&curr_ts);
-                    clip_and_mask::mask(&mask_node, mask, opt, bbox, layers, &mut sub_surface);
+                    perf::measure(perf::Phase::Mask, || {
+                        clip_and_mask::mask(&mask_node, mask, opt, bbox, layers, &mut sub_surface)
+                    });
                 }
             }
         }
@@ -356,17 +829,25 @@ fn render_group_impl(
 fn prepare_filter_background(
     parent: &usvg::Node,
     filter: &usvg::Filter,
+    img_size: ScreenSize,
     opt: &Options,
 ) -> Option<skia::Surface> {
     let start_node = crate::filter_background_start_node(parent, filter)?;
 
     let tree = parent.tree();
-    let (mut img, img_size) = create_root_image(tree.svg_node().size.to_screen_size(), opt)?;
+    // The background image must have the same size as the canvas,
+    // which is not the case when supersampling or rendering a node.
+    let mut img = create_surface(img_size, opt)?;
+    let region = ScreenRect::new(0, 0, img_size.width(), img_size.height())?;
+    fill_background(opt, img_size, region, &mut img);
     let view_box = tree.svg_node().view_box;
 
     // Render from the `start_node` until the `parent`. The `parent` itself is excluded.
     let mut state = RenderState::RenderUntil(parent.clone());
-    render_node_to_canvas_impl(&start_node, opt, view_box, img_size, &mut state, &mut img);
+    let mut layers = create_layers(img_size, opt);
+    render_node_to_canvas_impl(
+        &start_node, opt, view_box, img_size, &mut state, &mut layers, &mut img,
+    );
 
     Some(img)
 }
@@ -425,15 +906,30 @@ fn prepare_filter_stroke_paint(
 
 fn create_layers(
     img_size: ScreenSize,
+    opt: &Options,
 ) -> SkiaLayers {
-    layers::Layers::new(img_size, create_subimage, clear_image)
+    if opt.linear_compositing {
+        layers::Layers::new(img_size, create_linear_subimage, clear_image)
+    } else {
+        layers::Layers::new(img_size, create_subimage, clear_image)
+    }
 }
 
 fn create_subimage(
     size: ScreenSize,
 ) -> Option<skia::Surface> {
-    let mut img = try_create_surface!(size, None);
-    img.fill(0, 0, 0, 0);
+    // A new raster surface is zeroed, so there is no need to clear it.
+    Some(try_create_surface!(size, None))
+}
+
+fn create_linear_subimage(
+    size: ScreenSize,
+) -> Option<skia::Surface> {
+    let img = try_opt_warn_or!(
+        skia::Surface::new_rgba_premultiplied_linear(size.width(), size.height()),
+        None,
+        "Failed to create a {}x{} surface.", size.width(), size.height()
+    );
 
     Some(img)
 }
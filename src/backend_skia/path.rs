@@ -37,18 +37,37 @@ pub fn draw(
 
     let global_ts = usvg::Transform::from_native(&canvas.get_matrix());
 
-    if path.fill.is_some() {
+    let fill = if path.fill.is_some() {
         let mut fill = style::fill(tree, &path.fill, opt, style_bbox, global_ts);
         fill.set_anti_alias(antialias);
         fill.set_blend_mode(blend_mode);
-        canvas.draw_path(&skia_path, &fill);
-    }
+        Some(fill)
+    } else {
+        None
+    };
 
-    if path.stroke.is_some() {
+    let stroke = if path.stroke.is_some() {
         let mut stroke = style::stroke(tree, &path.stroke, opt, style_bbox, global_ts);
         stroke.set_anti_alias(antialias);
         stroke.set_blend_mode(blend_mode);
-        canvas.draw_path(&skia_path, &stroke);
+        Some(stroke)
+    } else {
+        None
+    };
+
+    match (fill, stroke) {
+        (Some(fill), Some(stroke)) => {
+            let order = match path.paint_order {
+                usvg::PaintOrder::FillAndStroke => skia::PaintOrder::FillFirst,
+                usvg::PaintOrder::StrokeAndFill => skia::PaintOrder::StrokeFirst,
+            };
+
+            canvas.draw_path_fill_stroke(&skia_path, &fill, &stroke, order);
+        }
+        (Some(paint), None) | (None, Some(paint)) => {
+            canvas.draw_path(&skia_path, &paint);
+        }
+        (None, None) => {}
     }
 
     bbox
@@ -58,9 +77,15 @@ fn convert_path(
     path: &usvg::PathData,
 ) -> skia::Path {
     let mut s_path = skia::Path::new();
+    let mut prev_seg = None;
     for seg in path.iter() {
         match *seg {
             usvg::PathSegment::MoveTo { x, y } => {
+                // A MoveTo followed by another MoveTo is a zero-length subpath too.
+                if let Some(usvg::PathSegment::MoveTo { x, y }) = prev_seg {
+                    s_path.line_to(x, y);
+                }
+
                 s_path.move_to(x, y);
             }
             usvg::PathSegment::LineTo { x, y } => {
@@ -70,9 +95,23 @@ fn convert_path(
                 s_path.cubic_to(x1, y1, x2, y2, x, y);
             }
             usvg::PathSegment::ClosePath => {
-                s_path.close();
+                if let Some(usvg::PathSegment::MoveTo { x, y }) = prev_seg {
+                    // A zero-length subpath, like `M 10 10 Z`, should still be stroked
+                    // with `round` or `square` caps. Skia will ignore a closed contour
+                    // without segments, so we have to emit a zero-length line instead.
+                    s_path.line_to(x, y);
+                } else {
+                    s_path.close();
+                }
             }
         }
+
+        prev_seg = Some(*seg);
+    }
+
+    // The same goes for a trailing MoveTo.
+    if let Some(usvg::PathSegment::MoveTo { x, y }) = prev_seg {
+        s_path.line_to(x, y);
     }
 
     s_path
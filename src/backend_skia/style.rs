@@ -20,8 +20,7 @@ pub fn fill(
         let opacity = fill.opacity;
         match fill.paint {
             usvg::Paint::Color(c) => {
-                let a = f64_bound(0.0, opacity.value() * 255.0, 255.0) as u8;
-                paint.set_color(c.red, c.green, c.blue, a);
+                paint.set_color_value(&skia::Color::from_usvg(c, opacity.value()));
             }
             usvg::Paint::Link(ref id) => {
                 if let Some(node) = tree.defs_by_id(id) {
@@ -59,8 +58,7 @@ pub fn stroke(
         let opacity = stroke.opacity;
         match stroke.paint {
             usvg::Paint::Color(c) => {
-                let a = f64_bound(0.0, opacity.value() * 255.0, 255.0) as u8;
-                paint.set_color(c.red, c.green, c.blue, a);
+                paint.set_color_value(&skia::Color::from_usvg(c, opacity.value()));
             }
             usvg::Paint::Link(ref id) => {
                 if let Some(node) = tree.defs_by_id(id) {
@@ -97,9 +95,11 @@ pub fn stroke(
         paint.set_stroke_miter(stroke.miterlimit.value());
         paint.set_stroke_width(stroke.width.value());
 
-        if let Some(ref list) = stroke.dasharray {
-            let list: Vec<_> = list.iter().map(|n| *n as f32).collect();
-            let path_effect = skia::PathEffect::new_dash_path(&list, stroke.dashoffset);
+        // Skia splits a path into dashes first and only then strokes it,
+        // so each dash gets its own caps and joins set above.
+        if let Some(list) = stroke.dasharray.as_ref().and_then(|list| dash_list(list)) {
+            let phase = dash_phase(&list, stroke.dashoffset);
+            let path_effect = skia::PathEffect::new_dash_path(&list, phase);
             paint.set_path_effect(path_effect);
         }
     }
@@ -107,6 +107,50 @@ pub fn stroke(
     paint
 }
 
+/// Converts `stroke-dasharray` into a list accepted by Skia.
+///
+/// `usvg` already normalizes the list, but `Stroke` can be created manually,
+/// while Skia requires an even number of non-negative intervals with a positive sum.
+/// Returns `None` when the stroke should not be dashed.
+fn dash_list(list: &[f64]) -> Option<Vec<f32>> {
+    if list.iter().any(|n| !n.is_finite() || *n < 0.0) {
+        return None;
+    }
+
+    let sum: f64 = list.iter().sum();
+    if sum.is_nan() || sum <= 0.0 {
+        return None;
+    }
+
+    let mut list: Vec<f32> = list.iter().map(|n| *n as f32).collect();
+
+    // An odd list is repeated to yield an even number of values.
+    if list.len() % 2 != 0 {
+        let copy = list.clone();
+        list.extend_from_slice(&copy);
+    }
+
+    Some(list)
+}
+
+/// Wraps `stroke-dashoffset` into the `[0, period)` range.
+///
+/// The offset can be negative or larger than the dash pattern itself,
+/// while Skia expects a phase inside the pattern.
+fn dash_phase(list: &[f32], offset: f32) -> f32 {
+    let period: f32 = list.iter().sum();
+    if period.is_nan() || period <= 0.0 || !offset.is_finite() {
+        return 0.0;
+    }
+
+    let phase = offset % period;
+    if phase < 0.0 {
+        phase + period
+    } else {
+        phase
+    }
+}
+
 fn prepare_linear(
     g: &usvg::LinearGradient,
     opacity: usvg::Opacity,
@@ -119,8 +163,7 @@ fn prepare_linear(
         base: prepare_base_gradient(g, opacity, &bbox)
     };
 
-    let shader = skia::Shader::new_linear_gradient(gradient);
-    paint.set_shader(&shader);
+    set_gradient_shader(&g.id, skia::Shader::new_linear_gradient(gradient), paint);
 }
 
 fn prepare_radial(
@@ -130,14 +173,35 @@ fn prepare_radial(
     paint: &mut skia::Paint,
 ) {
 
-    let gradient = skia::RadialGradient {
-        start_circle: (g.fx, g.fy, 0.0),
-        end_circle: (g.cx, g.cy, g.r.value()),
-        base: prepare_base_gradient(g, opacity, &bbox)
+    let base = prepare_base_gradient(g, opacity, &bbox);
+    let shader = if g.fx.fuzzy_eq(&g.cx) && g.fy.fuzzy_eq(&g.cy) {
+        skia::Shader::new_radial_gradient_simple((g.cx, g.cy), g.r.value(), base)
+    } else {
+        let gradient = skia::RadialGradient {
+            start_circle: (g.fx, g.fy, 0.0),
+            end_circle: (g.cx, g.cy, g.r.value()),
+            base,
+        };
+
+        skia::Shader::new_radial_gradient(gradient)
     };
 
-    let shader = skia::Shader::new_radial_gradient(gradient);
-    paint.set_shader(&shader);
+    set_gradient_shader(&g.id, shader, paint);
+}
+
+fn set_gradient_shader(
+    id: &str,
+    shader: Result<skia::Shader, skia::GradientError>,
+    paint: &mut skia::Paint,
+) {
+    match shader {
+        Ok(shader) => paint.set_shader(&shader),
+        Err(e) => {
+            // `usvg` should never produce such gradients, so this is just a safety net.
+            warn!("Gradient '{}' is invalid: {}. Skipped.", id, e);
+            paint.set_color(0, 0, 0, 0);
+        }
+    }
 }
 
 fn prepare_base_gradient(
@@ -166,8 +230,7 @@ fn prepare_base_gradient(
     let mut positions: Vec<f32> = Vec::new();
 
     for stop in &g.stops {
-        let a = (stop.opacity.value() * opacity.value() * 255.0) as u8;
-        let color = skia::Color::new(a, stop.color.red, stop.color.green, stop.color.blue);
+        let color = skia::Color::from_usvg(stop.color, stop.opacity.value() * opacity.value());
         colors.push(color.to_u32());
         positions.push(stop.offset.value() as f32);
     }
@@ -208,7 +271,7 @@ fn prepare_pattern(
         surface.scale(bbox.width(), bbox.height());
     }
 
-    let mut layers = super::create_layers(img_size);
+    let mut layers = super::create_layers(img_size, opt);
     super::render_group(pattern_node, opt, &mut RenderState::Ok, &mut layers, &mut surface);
 
     let mut ts = usvg::Transform::default();
@@ -223,3 +286,20 @@ fn prepare_pattern(
         paint.set_alpha(a);
     };
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dash_list_normalization() {
+        assert_eq!(dash_list(&[5.0, 2.0]), Some(vec![5.0, 2.0]));
+        assert_eq!(dash_list(&[5.0, 2.0, 1.0]), Some(vec![5.0, 2.0, 1.0, 5.0, 2.0, 1.0]));
+        assert_eq!(dash_list(&[0.0, 0.0]), None);
+        assert_eq!(dash_list(&[]), None);
+        assert_eq!(dash_list(&[5.0, -2.0]), None);
+        assert_eq!(dash_list(&[5.0, std::f64::NAN]), None);
+        assert_eq!(dash_list(&[5.0, std::f64::INFINITY]), None);
+    }
+}
@@ -0,0 +1,82 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A render time budget.
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+
+thread_local! {
+    static DEADLINE: Cell<Option<Instant>> = Cell::new(None);
+    static EXCEEDED: Cell<bool> = Cell::new(false);
+}
+
+/// Runs `f` with a time budget on the current thread.
+///
+/// Returns `None` when the budget was exceeded.
+/// A nested call cannot extend an outer budget.
+pub fn run<T, F: FnOnce() -> Option<T>>(budget: Duration, f: F) -> Option<T> {
+    let prev_deadline = DEADLINE.with(|d| d.get());
+    let deadline = match (prev_deadline, Instant::now().checked_add(budget)) {
+        (Some(prev), Some(new)) => Some(std::cmp::min(prev, new)),
+        (prev, new) => new.or(prev),
+    };
+
+    DEADLINE.with(|d| d.set(deadline));
+    let prev_exceeded = EXCEEDED.with(|e| e.replace(false));
+
+    let res = f();
+
+    let exceeded = EXCEEDED.with(|e| e.replace(prev_exceeded));
+    DEADLINE.with(|d| d.set(prev_deadline));
+
+    if exceeded {
+        None
+    } else {
+        res
+    }
+}
+
+/// Checks that the current budget is exceeded.
+///
+/// Always returns `false` outside of `run`.
+pub fn is_exceeded() -> bool {
+    if EXCEEDED.with(|e| e.get()) {
+        return true;
+    }
+
+    let deadline = try_opt_or!(DEADLINE.with(|d| d.get()), false);
+    if Instant::now() < deadline {
+        return false;
+    }
+
+    warn!("Render time budget was exceeded. Rendering aborted.");
+    EXCEEDED.with(|e| e.set(true));
+    true
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exceeded() {
+        assert!(!is_exceeded());
+
+        let res = run(Duration::from_secs(0), || {
+            assert!(is_exceeded());
+            Some(())
+        });
+        assert_eq!(res, None);
+
+        let res = run(Duration::from_secs(3600), || {
+            assert!(!is_exceeded());
+            Some(())
+        });
+        assert_eq!(res, Some(()));
+        assert!(!is_exceeded());
+    }
+}
@@ -0,0 +1,69 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Per-call rendering warnings.
+//!
+//! All rendering warnings are still passed to the `log` crate,
+//! but can also be collected without installing a global logger.
+//!
+//! Only warnings emitted by `resvg` itself during rendering are collected. Like:
+//!
+//! - unsupported or invalid filters, images and paint servers
+//! - external images that failed to load or were not allowed
+//! - skipped elements, e.g. because of the render depth limit or allocation failures
+//!
+//! Parsing warnings, like missing fonts, are emitted by `usvg` during `usvg::Tree` creation
+//! and are not collected.
+
+use std::cell::RefCell;
+
+
+thread_local! {
+    static MESSAGES: RefCell<Option<Vec<String>>> = RefCell::new(None);
+}
+
+/// Runs `f` and returns its result with all the warnings emitted during it
+/// on the current thread.
+pub fn collect<T, F: FnOnce() -> T>(f: F) -> (T, Vec<String>) {
+    let prev = MESSAGES.with(|m| m.replace(Some(Vec::new())));
+    let res = f();
+    let messages = MESSAGES.with(|m| m.replace(prev)).unwrap_or_default();
+
+    // Propagate to an outer `collect` call.
+    MESSAGES.with(|m| {
+        if let Some(ref mut outer) = *m.borrow_mut() {
+            outer.extend(messages.iter().cloned());
+        }
+    });
+
+    (res, messages)
+}
+
+/// Records a warning when collecting is active.
+pub(crate) fn record(msg: &str) {
+    MESSAGES.with(|m| {
+        if let Some(ref mut messages) = *m.borrow_mut() {
+            messages.push(msg.to_string());
+        }
+    });
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_collect() {
+        record("ignored");
+
+        let (_, outer) = collect(|| {
+            record("outer");
+            let (_, inner) = collect(|| record("inner"));
+            assert_eq!(inner, vec!["inner".to_string()]);
+        });
+
+        assert_eq!(outer, vec!["outer".to_string(), "inner".to_string()]);
+    }
+}
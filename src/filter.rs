@@ -4,10 +4,9 @@
 
 use std::rc::Rc;
 
-use log::warn;
 use usvg::ColorInterpolation as ColorSpace;
 
-use crate::prelude::*;
+use crate::{prelude::*, budget};
 
 
 pub enum Error {
@@ -182,8 +181,13 @@ pub trait Filter<T: ImageExt> {
     ) -> Result<(Image<T>, ScreenRect), Error> {
         let mut results = Vec::new();
         let region = calc_region(filter, bbox, ts, inputs.source)?;
+        let region = clamp_region(filter, region, opt.max_filter_region);
 
         for primitive in &filter.children {
+            if budget::is_exceeded() {
+                return Err(Error::NoResults);
+            }
+
             let cs = primitive.color_interpolation;
             let subregion = calc_subregion(filter, primitive, bbox, region, ts, &results)?;
 
@@ -437,6 +441,16 @@ pub trait Filter<T: ImageExt> {
         let (std_dx, std_dy) = Self::scale_coordinates(
             fe.std_dev_x.value(), fe.std_dev_y.value(), units, bbox, ts,
         )?;
+
+        // An absurd deviation will overflow the blur box size calculation.
+        // This limit is way above any meaningful value, so valid blurs are not affected.
+        const MAX_STD_DEV: f64 = 10_000.0;
+        if std_dx > MAX_STD_DEV || std_dy > MAX_STD_DEV {
+            warn!("feGaussianBlur stdDeviation is bigger than {}. Clamped.", MAX_STD_DEV);
+        }
+
+        let std_dx = std_dx.min(MAX_STD_DEV);
+        let std_dy = std_dy.min(MAX_STD_DEV);
         if std_dx.is_fuzzy_zero() && std_dy.is_fuzzy_zero() {
             None
         } else {
@@ -493,6 +507,25 @@ pub fn calc_region<T: ImageExt>(
     Ok(region)
 }
 
+/// Shrinks the filter region around its center to fit the `max` size.
+fn clamp_region(
+    filter: &usvg::Filter,
+    region: ScreenRect,
+    max: u32,
+) -> ScreenRect {
+    if region.width() <= max && region.height() <= max {
+        return region;
+    }
+
+    warn!("Filter '{}' region is bigger than {}x{}. Clipped.", filter.id, max, max);
+
+    let w = std::cmp::min(region.width(), max);
+    let h = std::cmp::min(region.height(), max);
+    let x = region.x() + (region.width() - w) as i32 / 2;
+    let y = region.y() + (region.height() - h) as i32 / 2;
+    ScreenRect::new(x, y, w, h).unwrap_or(region)
+}
+
 /// Returns filter primitive region.
 fn calc_subregion<T: ImageExt>(
     filter: &usvg::Filter,
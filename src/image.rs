@@ -4,8 +4,6 @@
 
 use std::{fs, path};
 
-use log::warn;
-
 use crate::prelude::*;
 
 
@@ -16,6 +14,8 @@ pub struct Image {
 
 pub enum ImageData {
     RGB(Vec<u8>),
+    // Produced only by the PNG decoder.
+    #[cfg_attr(not(feature = "png"), allow(dead_code))]
     RGBA(Vec<u8>),
 }
 
@@ -50,6 +50,10 @@ fn _load_raster(
 
     match data {
         usvg::ImageData::Path(ref path) => {
+            if !is_external_allowed(path, opt) {
+                return None;
+            }
+
             let path = get_abs_path(path, opt);
             let data = fs::read(path).ok()?;
 
@@ -69,6 +73,13 @@ fn _load_raster(
     }
 }
 
+#[cfg(not(feature = "png"))]
+fn read_png(_: &[u8]) -> Option<Image> {
+    warn!("PNG images are not supported, because resvg was built without the `png` feature.");
+    None
+}
+
+#[cfg(feature = "png")]
 fn read_png(data: &[u8]) -> Option<Image> {
     let decoder = png::Decoder::new(data);
     let (info, mut reader) = decoder.read_info().ok()?;
@@ -159,13 +170,24 @@ pub fn load_sub_svg(
             text_rendering: opt.usvg.text_rendering,
             image_rendering: opt.usvg.image_rendering,
             keep_named_groups: false,
+            image_href_resolver: opt.usvg.image_href_resolver.clone(),
+            allow_external_resources: opt.usvg.allow_external_resources,
+            fallback_paint: opt.usvg.fallback_paint,
         },
         fit_to: FitTo::Original,
         background: None,
+        linear_compositing: opt.linear_compositing,
+        supersample: 1,
+        max_render_depth: opt.max_render_depth,
+        max_filter_region: opt.max_filter_region,
     };
 
     let tree = match data {
         usvg::ImageData::Path(ref path) => {
+            if !is_external_allowed(path, opt) {
+                return None;
+            }
+
             let path = get_abs_path(path, opt);
             sub_opt.usvg.path = Some(path.clone());
             usvg::Tree::from_file(path, &sub_opt.usvg).ok()?
@@ -180,6 +202,15 @@ pub fn load_sub_svg(
     Some((tree, sub_opt))
 }
 
+fn is_external_allowed(path: &path::Path, opt: &Options) -> bool {
+    if !opt.usvg.allow_external_resources {
+        warn!("External image {:?} is not allowed. Skipped.", path);
+        return false;
+    }
+
+    true
+}
+
 fn sanitize_sub_svg(
     tree: &usvg::Tree,
 ) {
@@ -255,6 +286,95 @@ pub fn image_rect(
     new_size.to_size().to_rect(x, y)
 }
 
+/// Renders an image background.
+///
+/// The result is always opaque, so it can be copied into any surface as is.
+pub fn prepare_background(
+    background: Background,
+    size: ScreenSize,
+) -> Image {
+    let region = ScreenRect::new(0, 0, size.width(), size.height()).unwrap();
+    prepare_background_region(background, size, region)
+}
+
+/// Renders a `region` of an image background with the specified `size`.
+pub fn prepare_background_region(
+    background: Background,
+    size: ScreenSize,
+    region: ScreenRect,
+) -> Image {
+    let h = size.height();
+    let mut data = Vec::with_capacity(region.width() as usize * region.height() as usize * 3);
+    for y in region.top()..region.bottom() {
+        for x in region.left()..region.right() {
+            let (x, y) = (x as u32, y as u32);
+            let c = match background {
+                Background::Color(c) => c,
+                Background::LinearGradient { top, bottom } => {
+                    let t = if h > 1 { y as f64 / (h - 1) as f64 } else { 0.0 };
+                    let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+                    usvg::Color::new(
+                        lerp(top.red, bottom.red),
+                        lerp(top.green, bottom.green),
+                        lerp(top.blue, bottom.blue),
+                    )
+                }
+                Background::Checkerboard { size, light, dark } => {
+                    let size = std::cmp::max(size, 1);
+                    if (x / size + y / size) % 2 == 0 { light } else { dark }
+                }
+            };
+
+            data.push(c.red);
+            data.push(c.green);
+            data.push(c.blue);
+        }
+    }
+
+    Image {
+        data: ImageData::RGB(data),
+        size: region.size(),
+    }
+}
+
+/// Downscales a premultiplied RGBA/BGRA image by an integer `factor` using a box filter.
+///
+/// `dst` must be `factor` times smaller than `src` in each dimension.
+#[cfg(feature = "skia-backend")]
+pub fn downsample(
+    src: &[u8],
+    src_size: ScreenSize,
+    factor: u32,
+    dst: &mut [u8],
+) {
+    const CHANNELS: usize = 4;
+
+    let factor = factor as usize;
+    let src_width = src_size.width() as usize;
+    let dst_width = src_width / factor;
+    let dst_height = src_size.height() as usize / factor;
+    let area = (factor * factor) as u32;
+
+    for y in 0..dst_height {
+        for x in 0..dst_width {
+            let mut sum = [0u32; CHANNELS];
+            for sy in y * factor..(y + 1) * factor {
+                for sx in x * factor..(x + 1) * factor {
+                    let idx = (sy * src_width + sx) * CHANNELS;
+                    for (s, v) in sum.iter_mut().zip(&src[idx..idx + CHANNELS]) {
+                        *s += *v as u32;
+                    }
+                }
+            }
+
+            let idx = (y * dst_width + x) * CHANNELS;
+            for (d, s) in dst[idx..idx + CHANNELS].iter_mut().zip(&sum) {
+                *d = ((s + area / 2) / area) as u8;
+            }
+        }
+    }
+}
+
 fn get_abs_path(
     rel_path: &path::Path,
     opt: &Options,
@@ -264,3 +384,25 @@ fn get_abs_path(
         None => rel_path.into(),
     }
 }
+
+
+#[cfg(all(test, feature = "skia-backend"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downsample_edge() {
+        // A 4x2 image with a hard vertical edge that doesn't match the 2x2 grid.
+        let mut src = Vec::new();
+        for _ in 0..2 {
+            src.extend_from_slice(&[255, 255, 255, 255]);
+            src.extend_from_slice(&[255, 255, 255, 255]);
+            src.extend_from_slice(&[255, 255, 255, 255]);
+            src.extend_from_slice(&[0, 0, 0, 0]);
+        }
+
+        let mut dst = vec![0; 2 * 4];
+        downsample(&src, ScreenSize::new(4, 2).unwrap(), 2, &mut dst);
+        assert_eq!(dst, vec![255, 255, 255, 255, 128, 128, 128, 128]);
+    }
+}
@@ -20,22 +20,24 @@ pub struct Layers<T> {
     /// Use Rc as a shared counter.
     counter: Rc<()>,
     img_size: ScreenSize,
-    new_img_fn: fn(ScreenSize) -> Option<T>,
+    new_img_fn: Box<dyn FnMut(ScreenSize) -> Option<T>>,
     clear_img_fn: fn(&mut T),
 }
 
 impl<T> Layers<T> {
     /// Creates `Layers`.
-    pub fn new(
+    pub fn new<F>(
         img_size: ScreenSize,
-        new_img_fn: fn(ScreenSize) -> Option<T>,
+        new_img_fn: F,
         clear_img_fn: fn(&mut T),
-    ) -> Self {
+    ) -> Self
+        where F: FnMut(ScreenSize) -> Option<T> + 'static
+    {
         Layers {
             d: Vec::new(),
             counter: Rc::new(()),
             img_size,
-            new_img_fn,
+            new_img_fn: Box::new(new_img_fn),
             clear_img_fn,
         }
     }
@@ -48,6 +50,7 @@ impl<T> Layers<T> {
     /// Returns a first free layer to draw on.
     ///
     /// - If there are no free layers - will create a new one.
+    ///   `new_img_fn` must return a transparent image, so it will not be cleared.
     /// - If there is a free layer - it will clear it before return.
     /// - If a new layer allocation fail - will return `None`.
     pub fn get(&mut self) -> Option<Layer<T>> {
@@ -39,6 +39,15 @@ macro_rules! try_opt_or {
     };
 }
 
+/// Logs a warning and records it for `diagnostics::collect`.
+macro_rules! warn {
+    ($($arg:tt)+) => {{
+        let msg = format!($($arg)+);
+        crate::diagnostics::record(&msg);
+        log::warn!("{}", msg);
+    }};
+}
+
 /// Unwraps `Option` and invokes `return` on `None` with a warning.
 #[allow(unused_macros)]
 macro_rules! try_opt_warn {
@@ -46,7 +55,7 @@ macro_rules! try_opt_warn {
         match $task {
             Some(v) => v,
             None => {
-                log::warn!($msg);
+                warn!($msg);
                 return;
             }
         }
@@ -55,7 +64,7 @@ macro_rules! try_opt_warn {
         match $task {
             Some(v) => v,
             None => {
-                log::warn!($fmt, $($arg)*);
+                warn!($fmt, $($arg)*);
                 return;
             }
         }
@@ -69,7 +78,7 @@ macro_rules! try_opt_warn_or {
         match $task {
             Some(v) => v,
             None => {
-                log::warn!($msg);
+                warn!($msg);
                 return $ret;
             }
         }
@@ -78,7 +87,7 @@ macro_rules! try_opt_warn_or {
         match $task {
             Some(v) => v,
             None => {
-                log::warn!($fmt, $($arg)*);
+                warn!($fmt, $($arg)*);
                 return $ret;
             }
         }
@@ -99,6 +108,8 @@ pub use raqote;
 
 pub use usvg::{self, Error};
 
+use usvg::NodeExt;
+
 
 #[cfg(feature = "cairo-backend")]
 pub mod backend_cairo;
@@ -112,21 +123,29 @@ pub mod backend_skia;
 #[cfg(feature = "raqote-backend")]
 pub mod backend_raqote;
 
+pub mod diagnostics;
+pub mod perf;
 pub mod utils;
+mod atlas;
+mod budget;
 mod filter;
 mod geom;
 mod image;
 mod layers;
+mod merge_fills;
 mod options;
+mod unsupported;
 
 /// Commonly used types and traits.
 pub mod prelude {
     pub use usvg::{self, prelude::*};
-    pub use crate::{geom::*, options::*, utils, OutputImage, Render};
+    pub use crate::{geom::*, options::*, utils, OutputImage, PixelFormat, Render};
 }
 
+pub use crate::atlas::*;
 pub use crate::geom::*;
 pub use crate::options::*;
+pub use crate::unsupported::*;
 
 
 /// A generic interface for image rendering.
@@ -153,14 +172,50 @@ pub trait Render {
     ) -> Option<Box<dyn OutputImage>>;
 }
 
+/// An RGBA pixel data format.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PixelFormat {
+    /// RGBA with a straight (unmultiplied) alpha.
+    RgbaStraight,
+    /// RGBA with a premultiplied alpha.
+    RgbaPremultiplied,
+}
+
 /// A generic interface for output image.
+///
+/// All backends are using images with a premultiplied alpha internally,
+/// while `make_rgba_vec` returns a straight alpha.
+/// Use `make_rgba_vec_with_format` to select the required format explicitly.
 pub trait OutputImage {
+    /// Returns an image size.
+    fn size(&self) -> ScreenSize;
+
     /// Saves rendered image to the selected path.
     fn save_png(
         &mut self,
         path: &std::path::Path,
     ) -> bool;
 
+    /// Saves rendered image to the selected path and marks it as sRGB.
+    ///
+    /// Writes an `sRGB` PNG chunk, so color-managed viewers will not reinterpret colors.
+    /// Pixels are not converted, since all backends are already rendering in sRGB.
+    fn save_png_with_srgb(
+        &mut self,
+        path: &std::path::Path,
+    ) -> bool {
+        if !self.save_png(path) {
+            return false;
+        }
+
+        let mut data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(_) => return false,
+        };
+
+        utils::add_png_srgb_chunk(&mut data) && std::fs::write(path, data).is_ok()
+    }
+
     /// Converts an image's internal data into a `Vec<u8>`.
     ///
     /// Channels order and alpha multiplication will be different for each backend.
@@ -170,6 +225,36 @@ pub trait OutputImage {
     ///
     /// Image will be converted into an unmultiplied RGBA array.
     fn make_rgba_vec(&mut self) -> Vec<u8>;
+
+    /// Converts an image into an `image::DynamicImage`.
+    ///
+    /// Pixels will be stored as unmultiplied RGBA, just like in `make_rgba_vec`.
+    #[cfg(feature = "image-crate")]
+    fn make_dynamic_image(&mut self) -> ::image::DynamicImage {
+        let size = self.size();
+        let data = self.make_rgba_vec();
+        let buf = ::image::RgbaImage::from_raw(size.width(), size.height(), data)
+            .expect("an RGBA buffer should match the image size");
+        ::image::DynamicImage::ImageRgba8(buf)
+    }
+
+    /// Converts an image's internal data into an RGBA `Vec<u8>` with the specified alpha format.
+    fn make_rgba_vec_with_format(&mut self, format: PixelFormat) -> Vec<u8> {
+        let mut data = self.make_rgba_vec();
+        utils::convert_pixel_format(&mut data, PixelFormat::RgbaStraight, format);
+        data
+    }
+
+    /// Converts an image into a packed 1-bit alpha mask.
+    ///
+    /// A bit is set when a pixel alpha is greater than or equal to the `threshold`.
+    /// The most significant bit of a byte represents the leftmost pixel.
+    /// Each row is padded to a whole byte, i.e. occupies `(width + 7) / 8` bytes.
+    fn make_alpha_mask(&mut self, threshold: u8) -> Vec<u8> {
+        let width = self.size().width() as usize;
+        let data = self.make_rgba_vec();
+        utils::alpha_mask(&data, width, threshold)
+    }
 }
 
 
@@ -203,6 +288,233 @@ pub fn default_backend() -> Box<dyn Render> {
     unreachable!("at least one backend must be enabled")
 }
 
+/// Parses and renders an SVG from a reader.
+///
+/// A shorthand for `usvg::Tree::from_reader` and `Render::render_to_image`.
+/// The SVG text is dropped before rendering, so only the tree is kept in memory
+/// alongside the image. See `usvg::Tree::from_reader` for the parsing memory usage.
+///
+/// Returns `Ok(None)` when the image allocation failed.
+pub fn render_from_reader<R: std::io::Read>(
+    backend: &dyn Render,
+    reader: R,
+    opt: &Options,
+) -> Result<Option<Box<dyn OutputImage>>, Error> {
+    let tree = perf::trace_parse(|| usvg::Tree::from_reader(reader, &opt.usvg))?;
+    Ok(backend.render_to_image(&tree, opt))
+}
+
+/// Renders the `tree` and returns all the warnings emitted during rendering.
+///
+/// See `diagnostics` for the list of reported conditions.
+pub fn render_with_diagnostics(
+    backend: &dyn Render,
+    tree: &usvg::Tree,
+    opt: &Options,
+) -> (Option<Box<dyn OutputImage>>, Vec<String>) {
+    diagnostics::collect(|| backend.render_to_image(tree, opt))
+}
+
+/// Renders the `tree` within a time `budget`.
+///
+/// Returns `None` when rendering took longer than `budget` or has failed.
+///
+/// Elapsed time is checked before rendering each node of a group,
+/// including clip paths, masks and patterns content, and before each filter primitive.
+/// So a single expensive path or a filter primitive can still overrun the budget.
+pub fn render_with_budget(
+    backend: &dyn Render,
+    tree: &usvg::Tree,
+    opt: &Options,
+    budget: std::time::Duration,
+) -> Option<Box<dyn OutputImage>> {
+    budget::run(budget, || backend.render_to_image(tree, opt))
+}
+
+/// Renders the `tree` with adjacent same-color fills merged.
+///
+/// Shapes that share an edge, like map regions or tiles, are usually rendered with a thin seam
+/// along it, because each shape is antialiased separately. This function merges consecutive
+/// sibling paths with the same solid fill, no stroke and the same transform into a single path,
+/// which doesn't have this issue.
+///
+/// Merged shapes should not overlap, since the overlapping area will be filled
+/// according to the fill rule of the merged path, i.e. may become a hole.
+///
+/// The `tree` itself is not modified. A copy is rendered instead.
+pub fn render_with_merged_fills(
+    backend: &dyn Render,
+    tree: &usvg::Tree,
+    opt: &Options,
+) -> Option<Box<dyn OutputImage>> {
+    let tree = tree.root().make_deep_copy().tree();
+    merge_fills::merge_fills(&tree);
+    backend.render_to_image(&tree, opt)
+}
+
+/// Renders the `tree` with node visibility overridden by ID.
+///
+/// Nodes with IDs from `visible` will be rendered even when they are hidden.
+/// And nodes from `hidden`, including their descendants, will not be rendered.
+/// When an ID is present in both sets, `hidden` takes precedence.
+/// Other nodes keep their authored visibility.
+///
+/// Since `visibility` is resolved by `usvg`, groups don't have one.
+/// So a group from `visible` is considered hidden when all its paths and images are hidden,
+/// in which case they will be shown. Otherwise, the group content is left as is,
+/// which preserves hidden elements inside a visible layer.
+///
+/// Only IDs preserved by `usvg` can be used. So groups require
+/// `usvg::Options::keep_named_groups` to be set.
+///
+/// The `tree` itself is not modified. A copy is rendered instead.
+pub fn render_with_visibility(
+    backend: &dyn Render,
+    tree: &usvg::Tree,
+    opt: &Options,
+    visible: &std::collections::HashSet<String>,
+    hidden: &std::collections::HashSet<String>,
+) -> Option<Box<dyn OutputImage>> {
+    fn visibility_mut(kind: &mut usvg::NodeKind) -> Option<&mut usvg::Visibility> {
+        match *kind {
+            usvg::NodeKind::Path(ref mut path) => Some(&mut path.visibility),
+            usvg::NodeKind::Image(ref mut img) => Some(&mut img.visibility),
+            _ => None,
+        }
+    }
+
+    fn apply(tree: &usvg::Tree, ids: &std::collections::HashSet<String>, v: usvg::Visibility) {
+        for node in tree.root().descendants() {
+            if tree.is_in_defs(&node) || !ids.contains(&*node.id()) {
+                continue;
+            }
+
+            if v == usvg::Visibility::Visible {
+                // Do not override hidden elements inside a visible group.
+                let is_hidden = node.descendants().all(|n| {
+                    match *n.borrow() {
+                        usvg::NodeKind::Path(ref path) => path.visibility != usvg::Visibility::Visible,
+                        usvg::NodeKind::Image(ref img) => img.visibility != usvg::Visibility::Visible,
+                        _ => true,
+                    }
+                });
+
+                if !is_hidden {
+                    continue;
+                }
+            }
+
+            for mut child in node.descendants() {
+                if let Some(visibility) = visibility_mut(&mut child.borrow_mut()) {
+                    *visibility = v;
+                }
+            }
+        }
+    }
+
+    let tree = tree.root().make_deep_copy().tree();
+    apply(&tree, visible, usvg::Visibility::Visible);
+    apply(&tree, hidden, usvg::Visibility::Hidden);
+    backend.render_to_image(&tree, opt)
+}
+
+/// Renders the `tree` and calls `hook` for each rendered node.
+///
+/// The `hook` is called in document order with a node and its bounding box
+/// in the output image coordinates, i.e. with `Options::fit_to` applied.
+/// This allows aligning an overlay, like selection handles, with rendered elements.
+///
+/// Only paths, images and groups outside of `defs` are reported.
+/// Hidden paths and images, as well as empty groups, are skipped.
+/// The `hook` is called after the whole image has been rendered.
+///
+/// Mutating the `tree` from the `hook` is not supported.
+pub fn render_with_hook(
+    backend: &dyn Render,
+    tree: &usvg::Tree,
+    opt: &Options,
+    hook: &mut dyn FnMut(&usvg::Node, &Rect),
+) -> Option<Box<dyn OutputImage>> {
+    let img = backend.render_to_image(tree, opt)?;
+
+    let view_box = tree.svg_node().view_box;
+    let ts = utils::view_box_to_transform(view_box.rect, view_box.aspect, img.size().to_size());
+    for node in tree.root().descendants().skip(1) {
+        if tree.is_in_defs(&node) {
+            continue;
+        }
+
+        match *node.borrow() {
+            usvg::NodeKind::Path(ref path) if path.visibility == usvg::Visibility::Visible => {}
+            usvg::NodeKind::Image(ref img) if img.visibility == usvg::Visibility::Visible => {}
+            usvg::NodeKind::Group(_) => {}
+            _ => continue,
+        }
+
+        let bbox = match node.calculate_bbox() {
+            // An empty group.
+            Some(bbox) if bbox.x() == std::f64::MAX => continue,
+            Some(bbox) => bbox,
+            None => continue,
+        };
+
+        let path = usvg::PathData::from_rect(bbox);
+        if let Some(bbox) = path.bbox_with_transform(ts, None) {
+            hook(&node, &bbox);
+        }
+    }
+
+    Some(img)
+}
+
+/// Returns an image size that `Render::render_to_image` will produce for the `tree`.
+///
+/// Uses the same math as the backends, but without allocating an image.
+/// `Options::supersample` doesn't affect the output size, so it's not needed here.
+///
+/// Returns `None` when the resulting size is zero.
+pub fn fit_size(tree: &usvg::Tree, fit_to: FitTo) -> Option<ScreenSize> {
+    utils::fit_to(tree.svg_node().size.to_screen_size(), fit_to)
+}
+
+/// Returns font families used by the `tree` text.
+///
+/// Families are deduplicated and listed in the order they appear in the document.
+/// Those are the authored `font-family` names, not the faces that were actually resolved,
+/// so a family can be listed even when it's not installed.
+///
+/// See `usvg::Tree::font_families` for details.
+pub fn required_fonts(tree: &usvg::Tree) -> Vec<String> {
+    tree.font_families().to_vec()
+}
+
+/// Checks that the `tree` has rendered text.
+///
+/// Text is converted into paths by `usvg`, so this is a cheap check of
+/// `usvg::Tree::font_families`, which is not empty for any rendered text.
+/// Allows to detect documents that will look differently depending on installed fonts.
+///
+/// Always returns `false` when `usvg` was built without the `text` feature.
+pub fn has_text(tree: &usvg::Tree) -> bool {
+    !tree.font_families().is_empty()
+}
+
+/// Returns a `FitTo` that fits the `tree` into a `max_width` x `max_height` box,
+/// preserving the aspect ratio.
+///
+/// Images that already fit are not upscaled, i.e. `FitTo::Original` is returned.
+/// Use `fit_within_upscaled` to always fill the box.
+///
+/// A zero `max_width` or `max_height` results in a `FitTo` that cannot be rendered.
+pub fn fit_within(tree: &usvg::Tree, max_width: u32, max_height: u32) -> FitTo {
+    utils::fit_within(tree.svg_node().size.to_screen_size(), max_width, max_height, false)
+}
+
+/// Like `fit_within`, but upscales images smaller than the box.
+pub fn fit_within_upscaled(tree: &usvg::Tree, max_width: u32, max_height: u32) -> FitTo {
+    utils::fit_within(tree.svg_node().size.to_screen_size(), max_width, max_height, true)
+}
+
 pub(crate) fn use_shape_antialiasing(
     mode: usvg::ShapeRendering,
 ) -> bool {
@@ -261,6 +573,37 @@ pub(crate) enum RenderState {
 }
 
 
+thread_local! {
+    static RENDER_DEPTH: std::cell::Cell<u32> = std::cell::Cell::new(0);
+}
+
+/// A render nesting depth guard.
+///
+/// Increments the current thread render depth while alive.
+pub(crate) struct RenderDepthGuard(());
+
+impl RenderDepthGuard {
+    /// Returns `None` when `Options::max_render_depth` was reached.
+    pub fn enter(opt: &Options) -> Option<Self> {
+        let depth = RENDER_DEPTH.with(|d| d.get());
+        if depth >= opt.max_render_depth {
+            warn!("Render depth limit of {} was reached. Subtree skipped.",
+                  opt.max_render_depth);
+            return None;
+        }
+
+        RENDER_DEPTH.with(|d| d.set(depth + 1));
+        Some(RenderDepthGuard(()))
+    }
+}
+
+impl Drop for RenderDepthGuard {
+    fn drop(&mut self) {
+        RENDER_DEPTH.with(|d| d.set(d.get() - 1));
+    }
+}
+
+
 /// Returns the node starting from which the filter background should be rendered.
 pub(crate) fn filter_background_start_node(
     parent: &usvg::Node,
@@ -283,3 +626,594 @@ pub(crate) fn filter_background_start_node(
     // Skip the current element.
     parent.ancestors().skip(1).find(|node| has_enable_background(node))
 }
+
+
+// All tests here render images, so they require at least one backend.
+#[cfg(all(test, any(
+    feature = "cairo-backend",
+    feature = "qt-backend",
+    feature = "skia-backend",
+    feature = "raqote-backend",
+)))]
+mod tests {
+    use super::*;
+
+    fn backends() -> Vec<Box<dyn Render>> {
+        let mut list: Vec<Box<dyn Render>> = Vec::new();
+
+        #[cfg(feature = "cairo-backend")]
+        list.push(Box::new(backend_cairo::Backend));
+
+        #[cfg(feature = "qt-backend")]
+        list.push(Box::new(backend_qt::Backend));
+
+        #[cfg(feature = "skia-backend")]
+        list.push(Box::new(backend_skia::Backend));
+
+        #[cfg(feature = "raqote-backend")]
+        list.push(Box::new(backend_raqote::Backend));
+
+        assert!(!list.is_empty());
+        list
+    }
+
+    #[test]
+    fn transparent_background() {
+        let tree = usvg::Tree::from_str(
+            "<svg width='20' height='20' xmlns='http://www.w3.org/2000/svg'>\
+                 <rect x='5' y='5' width='10' height='10' fill='green'/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        for backend in backends() {
+            let mut img = backend.render_to_image(&tree, &Options::default()).unwrap();
+            let data = img.make_rgba_vec();
+            for &(x, y) in &[(0, 0), (19, 0), (0, 19), (19, 19)] {
+                let idx = (y * 20 + x) * 4;
+                assert_eq!(&data[idx..idx + 4], &[0, 0, 0, 0]);
+            }
+            assert_eq!(data[(10 * 20 + 10) * 4 + 3], 255);
+        }
+    }
+
+    #[test]
+    fn flood_tile() {
+        // A 5x5 flood inside a 10x10 tile, repeated over the whole region.
+        let tree = usvg::Tree::from_str(
+            "<svg width='40' height='40' xmlns='http://www.w3.org/2000/svg'>\
+                 <filter id='filter1' filterUnits='userSpaceOnUse' x='0' y='0' width='40' height='40'>\
+                     <feFlood flood-color='green' x='0' y='0' width='5' height='5'/>\
+                     <feOffset x='0' y='0' width='10' height='10'/>\
+                     <feTile/>\
+                 </filter>\
+                 <rect width='40' height='40' filter='url(#filter1)'/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        for backend in backends() {
+            let mut img = backend.render_to_image(&tree, &Options::default()).unwrap();
+            let data = img.make_rgba_vec();
+            let alpha = |x: usize, y: usize| data[(y * 40 + x) * 4 + 3];
+            for &(x, y) in &[(2, 2), (12, 2), (22, 32), (32, 12)] {
+                assert_eq!(alpha(x, y), 255);
+            }
+            for &(x, y) in &[(7, 7), (17, 2), (2, 27), (37, 37)] {
+                assert_eq!(alpha(x, y), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn render_hook() {
+        let tree = usvg::Tree::from_str(
+            "<svg width='20' height='20' xmlns='http://www.w3.org/2000/svg'>\
+                 <rect id='rect1' x='5' y='5' width='10' height='5'/>\
+                 <g id='g1' transform='translate(5 10)'>\
+                     <rect id='rect2' width='5' height='5'/>\
+                 </g>\
+             </svg>",
+            &usvg::Options { keep_named_groups: true, ..usvg::Options::default() },
+        ).unwrap();
+
+        let opt = Options { fit_to: FitTo::Zoom(2.0), ..Options::default() };
+        for backend in backends() {
+            let mut nodes = Vec::new();
+            render_with_hook(backend.as_ref(), &tree, &opt, &mut |node, bbox| {
+                let rect = (bbox.x(), bbox.y(), bbox.width(), bbox.height());
+                nodes.push((node.id().to_string(), rect));
+            }).unwrap();
+
+            assert_eq!(nodes, vec![
+                ("rect1".to_string(), (10.0, 10.0, 20.0, 10.0)),
+                ("g1".to_string(), (10.0, 20.0, 10.0, 10.0)),
+                ("rect2".to_string(), (10.0, 20.0, 10.0, 10.0)),
+            ]);
+        }
+    }
+
+    #[test]
+    fn premultiplied_output() {
+        let tree = usvg::Tree::from_str(
+            "<svg width='20' height='20' xmlns='http://www.w3.org/2000/svg'>\
+                 <rect width='20' height='20' fill='#FF0000' fill-opacity='0.1'/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        for backend in backends() {
+            let mut img = backend.render_to_image(&tree, &Options::default()).unwrap();
+
+            let straight = img.make_rgba_vec_with_format(PixelFormat::RgbaStraight);
+            assert_eq!(&straight[0..4], &[255, 0, 0, straight[3]]);
+
+            // Premultiplied data must be the internal one, only with a different channels order.
+            let premultiplied = img.make_rgba_vec_with_format(PixelFormat::RgbaPremultiplied);
+            let a = premultiplied[3];
+            assert!(a > 0 && a < 255);
+            assert_eq!(&premultiplied[0..4], &[a, 0, 0, a]);
+
+            let internal = img.make_vec();
+            for (p1, p2) in premultiplied.chunks(4).zip(internal.chunks(4)) {
+                let mut p1 = p1.to_vec();
+                let mut p2 = p2.to_vec();
+                p1.sort();
+                p2.sort();
+                assert_eq!(p1, p2);
+            }
+        }
+    }
+
+    #[test]
+    fn export_stroke_only_node() {
+        let tree = usvg::Tree::from_str(
+            "<svg width='80' height='40' xmlns='http://www.w3.org/2000/svg'>\
+                 <g transform='scale(2)'>\
+                     <line id='line1' x1='10' y1='10' x2='30' y2='10' stroke='black' stroke-width='4'/>\
+                 </g>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        let node = tree.node_by_id("line1").unwrap();
+        let bbox = utils::node_visual_bbox(&node).unwrap();
+        assert_eq!((bbox.x(), bbox.y(), bbox.width(), bbox.height()), (16.0, 16.0, 48.0, 8.0));
+
+        for backend in backends() {
+            let mut img = backend.render_node_to_image(&node, &Options::default()).unwrap();
+            assert_eq!(img.size().dimensions(), (48, 8));
+
+            // The stroke must not be cut off.
+            let data = img.make_rgba_vec();
+            for y in 0..8 {
+                assert_eq!(data[(y * 48 + 24) * 4 + 3], 255);
+            }
+        }
+    }
+
+    #[test]
+    fn rotated_gradient() {
+        // A horizontal gradient rotated by 45 degrees becomes a diagonal one.
+        let tree = usvg::Tree::from_str(
+            "<svg width='20' height='20' xmlns='http://www.w3.org/2000/svg'>\
+                 <linearGradient id='lg1' gradientTransform='rotate(45)'>\
+                     <stop offset='0' stop-color='red'/>\
+                     <stop offset='1' stop-color='blue'/>\
+                 </linearGradient>\
+                 <rect width='20' height='20' fill='url(#lg1)'/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        for backend in backends() {
+            let mut img = backend.render_to_image(&tree, &Options::default()).unwrap();
+            let data = img.make_rgba_vec();
+            let red = |x: usize, y: usize| data[(y * 20 + x) * 4] as i32;
+
+            // Pixels on the anti-diagonal have the same color...
+            assert!((red(18, 1) - red(1, 18)).abs() <= 2);
+            assert!((red(14, 5) - red(5, 14)).abs() <= 2);
+            // ...while the color changes along the diagonal.
+            assert!(red(1, 1) > red(10, 10) + 50);
+            assert!(red(10, 10) > red(18, 18) + 50);
+        }
+    }
+
+    #[test]
+    fn absurd_blur() {
+        let tree = usvg::Tree::from_str(
+            "<svg width='200' height='200' xmlns='http://www.w3.org/2000/svg'>\
+                 <filter id='filter1' filterUnits='userSpaceOnUse' x='-1e9' y='-1e9' width='2e9' height='2e9'>\
+                     <feGaussianBlur stdDeviation='1e30'/>\
+                 </filter>\
+                 <rect x='20' y='20' width='160' height='160' filter='url(#filter1)'/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        for backend in backends() {
+            let (img, warnings) = render_with_diagnostics(backend.as_ref(), &tree, &Options::default());
+            let mut img = img.unwrap();
+            assert_eq!(img.size().dimensions(), (200, 200));
+            assert!(warnings.iter().any(|w| w.contains("Clamped")));
+
+            // The filter region is limited by the canvas and the blur is still applied.
+            let data = img.make_rgba_vec();
+            assert!(data[(100 * 200 + 100) * 4 + 3] < 255);
+        }
+    }
+
+    #[test]
+    fn max_filter_region() {
+        let tree = usvg::Tree::from_str(
+            "<svg width='200' height='200' xmlns='http://www.w3.org/2000/svg'>\
+                 <filter id='filter1' filterUnits='userSpaceOnUse' x='0' y='0' width='200' height='200'>\
+                     <feFlood flood-color='green'/>\
+                 </filter>\
+                 <rect width='200' height='200' filter='url(#filter1)'/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        let opt = Options { max_filter_region: 100, ..Options::default() };
+        for backend in backends() {
+            let (img, warnings) = render_with_diagnostics(backend.as_ref(), &tree, &opt);
+            let mut img = img.unwrap();
+            assert!(warnings.iter().any(|w| w.contains("region is bigger than 100x100")));
+
+            // The region is clipped around its center.
+            let data = img.make_rgba_vec();
+            assert_eq!(data[(100 * 200 + 100) * 4 + 3], 255);
+            assert_eq!(data[(10 * 200 + 10) * 4 + 3], 0);
+            assert_eq!(data[(190 * 200 + 190) * 4 + 3], 0);
+        }
+
+        // Regions within the limit are not affected.
+        for backend in backends() {
+            let (img, warnings) = render_with_diagnostics(backend.as_ref(), &tree, &Options::default());
+            assert!(warnings.is_empty());
+            let data = img.unwrap().make_rgba_vec();
+            assert_eq!(data[(10 * 200 + 10) * 4 + 3], 255);
+        }
+    }
+
+    #[cfg(feature = "skia-backend")]
+    #[test]
+    fn supersample_thin_stroke() {
+        // Antialiasing is disabled, so only supersampling can produce a partial coverage.
+        let tree = usvg::Tree::from_str(
+            "<svg width='20' height='20' xmlns='http://www.w3.org/2000/svg'>\
+                 <line x1='0' y1='6' x2='20' y2='6' stroke='black' shape-rendering='crispEdges'/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        let edge_alpha = |supersample| {
+            let opt = Options { supersample, ..Options::default() };
+            let mut img = backend_skia::Backend.render_to_image(&tree, &opt).unwrap();
+            let data = img.make_rgba_vec();
+            (data[(5 * 20 + 10) * 4 + 3], data[(6 * 20 + 10) * 4 + 3])
+        };
+
+        let (a1, a2) = edge_alpha(1);
+        assert!((a1 == 0 || a1 == 255) && (a2 == 0 || a2 == 255));
+
+        let (a1, a2) = edge_alpha(2);
+        assert!(a1 > 0 && a1 < 255);
+        assert!(a2 > 0 && a2 < 255);
+    }
+
+    #[cfg(feature = "skia-backend")]
+    #[test]
+    fn zero_length_subpaths() {
+        // Each MoveTo starts a zero-length subpath, which must be drawn as a round cap.
+        let tree = usvg::Tree::from_str(
+            "<svg width='30' height='20' xmlns='http://www.w3.org/2000/svg'>\
+                 <path d='M 5 10 M 15 10 M 25 10' stroke='black' stroke-width='4' \
+                       stroke-linecap='round'/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        let mut img = backend_skia::Backend.render_to_image(&tree, &Options::default()).unwrap();
+        let data = img.make_rgba_vec();
+        let alpha = |x: usize, y: usize| data[(y * 30 + x) * 4 + 3];
+        for &x in &[5, 15, 25] {
+            assert!(alpha(x, 10) > 0);
+        }
+        for &x in &[10, 20] {
+            assert_eq!(alpha(x, 10), 0);
+        }
+    }
+
+    #[cfg(feature = "skia-backend")]
+    #[test]
+    fn render_region_in_document_units() {
+        let tree = usvg::Tree::from_str(
+            "<svg width='20' height='20' viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>\
+                 <rect x='5' y='5' width='5' height='5' fill='green'/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        let opt = Options { fit_to: FitTo::Zoom(2.0), ..Options::default() };
+        let dirty = Rect::new(4.5, 4.5, 10.0, 10.0).unwrap();
+        let region = backend_skia::region_to_screen_rect(&tree, &opt, dirty).unwrap();
+        assert_eq!(region, ScreenRect::new(18, 18, 22, 22).unwrap());
+
+        let mut img = backend_skia::render_region(&tree, &opt, dirty).unwrap();
+        assert_eq!(img.size().dimensions(), (22, 22));
+        let data = img.make_rgba_vec();
+        let alpha = |x: usize, y: usize| data[(y * 22 + x) * 4 + 3];
+        assert_eq!(alpha(0, 0), 0);
+        assert_eq!(alpha(2, 2), 255);
+        assert_eq!(alpha(21, 21), 255);
+
+        let outside = Rect::new(20.0, 20.0, 5.0, 5.0).unwrap();
+        assert!(backend_skia::render_region(&tree, &opt, outside).is_none());
+    }
+
+    #[cfg(feature = "skia-backend")]
+    #[test]
+    fn linear_compositing() {
+        // The red rect edge covers half of the 10th column.
+        let tree = usvg::Tree::from_str(
+            "<svg width='20' height='20' xmlns='http://www.w3.org/2000/svg'>\
+                 <rect width='20' height='20' fill='blue'/>\
+                 <rect width='10.5' height='20' fill='red'/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        let edge = |linear_compositing| {
+            let opt = Options { linear_compositing, ..Options::default() };
+            let mut img = backend_skia::Backend.render_to_image(&tree, &opt).unwrap();
+            let data = img.make_rgba_vec();
+            let idx = (10 * 20 + 10) * 4;
+            (data[idx], data[idx + 2], data[idx + 3], data[(10 * 20 + 5) * 4])
+        };
+
+        // In sRGB, a half-covered pixel is a dark purple.
+        let (r, b, a, solid) = edge(false);
+        assert!(r > 100 && r < 155 && b > 100 && b < 155);
+        assert_eq!((a, solid), (255, 255));
+
+        // In linear RGB, it is much brighter, while solid colors are not affected.
+        let (r, b, a, solid) = edge(true);
+        assert!(r > 165 && b > 165);
+        assert_eq!((a, solid), (255, 255));
+    }
+
+    #[cfg(feature = "skia-backend")]
+    #[test]
+    fn linear_compositing_mask_and_filter() {
+        // A mask luminance and filters must be calculated in sRGB
+        // and produce the same result with both compositing modes.
+        let tree = usvg::Tree::from_str(
+            "<svg width='20' height='20' xmlns='http://www.w3.org/2000/svg'>\
+                 <mask id='mask1'><rect width='10' height='20' fill='#808080'/></mask>\
+                 <filter id='filter1' color-interpolation-filters='sRGB'>\
+                     <feOffset dx='0' dy='0'/>\
+                 </filter>\
+                 <rect width='20' height='20' fill='black' mask='url(#mask1)'/>\
+                 <rect x='10' width='10' height='20' fill='#808080' filter='url(#filter1)'/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        let pixels = |linear_compositing| {
+            let opt = Options { linear_compositing, ..Options::default() };
+            let mut img = backend_skia::Backend.render_to_image(&tree, &opt).unwrap();
+            let data = img.make_rgba_vec();
+            let masked = data[(10 * 20 + 5) * 4 + 3];
+            let filtered = data[(10 * 20 + 15) * 4];
+            (masked, filtered)
+        };
+
+        let (masked1, filtered1) = pixels(false);
+        let (masked2, filtered2) = pixels(true);
+        assert!((masked1 as i32 - 128).abs() <= 2);
+        assert!((masked1 as i32 - masked2 as i32).abs() <= 2);
+        assert!((filtered1 as i32 - 128).abs() <= 2);
+        assert!((filtered1 as i32 - filtered2 as i32).abs() <= 2);
+    }
+
+    #[cfg(feature = "skia-backend")]
+    #[test]
+    fn renderer_reuses_largest_layers() {
+        // A group opacity, a clip path and a filter are rendered using layers.
+        let tree = usvg::Tree::from_str(
+            "<svg width='20' height='20' xmlns='http://www.w3.org/2000/svg'>\
+                 <clipPath id='clip1'><circle cx='10' cy='10' r='8'/></clipPath>\
+                 <filter id='filter1'><feGaussianBlur stdDeviation='1'/></filter>\
+                 <g opacity='0.5' clip-path='url(#clip1)'>\
+                     <rect width='20' height='20' fill='green' filter='url(#filter1)'/>\
+                     <rect x='5' y='5' width='5' height='5' fill='blue'/>\
+                 </g>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        let mut renderer = backend_skia::Renderer::new();
+        for &zoom in &[2.0, 1.0, 1.5, 2.0] {
+            let opt = Options { fit_to: FitTo::Zoom(zoom), ..Options::default() };
+            let mut expected = backend_skia::render_to_image(&tree, &opt).unwrap();
+            let mut img = renderer.render(&tree, &opt).unwrap();
+            assert_eq!(img.size(), expected.size());
+            assert_eq!(img.make_vec(), expected.make_vec());
+            assert_eq!(renderer.buffer_size(), (40, 40));
+        }
+    }
+
+    #[cfg(feature = "skia-backend")]
+    #[test]
+    fn render_clipped() {
+        let tree = usvg::Tree::from_str(
+            "<svg width='20' height='20' xmlns='http://www.w3.org/2000/svg'>\
+                 <rect width='20' height='20' fill='green'/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        let render = |clip| {
+            let mut img = backend_skia::render_clipped(&tree, &Options::default(), clip).unwrap();
+            assert_eq!(img.size().dimensions(), (20, 20));
+            img.make_rgba_vec()
+        };
+
+        // A clip outside the image produces a transparent image.
+        let data = render(ScreenRect::new(30, 30, 10, 10).unwrap());
+        assert!(data.iter().all(|&c| c == 0));
+
+        // A clip that is partially outside the image.
+        let data = render(ScreenRect::new(-5, -5, 10, 10).unwrap());
+        let alpha = |x: usize, y: usize| data[(y * 20 + x) * 4 + 3];
+        assert_eq!(alpha(0, 0), 255);
+        assert_eq!(alpha(4, 4), 255);
+        assert_eq!(alpha(5, 5), 0);
+        assert_eq!(alpha(4, 5), 0);
+    }
+
+    #[test]
+    fn has_text() {
+        let parse = |text| usvg::Tree::from_str(text, &usvg::Options::default()).unwrap();
+
+        let tree = parse(
+            "<svg width='20' height='20' xmlns='http://www.w3.org/2000/svg'>\
+                 <rect width='20' height='20'/>\
+                 <defs><text>Unused</text></defs>\
+             </svg>"
+        );
+        assert!(!super::has_text(&tree));
+
+        let tree = parse(
+            "<svg width='20' height='20' xmlns='http://www.w3.org/2000/svg'>\
+                 <text x='2' y='10' font-family='Noto Sans'>Text</text>\
+             </svg>"
+        );
+        assert_eq!(super::has_text(&tree), cfg!(feature = "text"));
+    }
+
+    #[cfg(feature = "skia-backend")]
+    #[test]
+    fn knockout_group() {
+        let tree = usvg::Tree::from_str(
+            "<svg width='20' height='20' xmlns='http://www.w3.org/2000/svg'>\
+                 <g id='g1'>\
+                     <rect width='15' height='20' fill='red'/>\
+                     <rect x='5' width='15' height='20' fill='blue' fill-opacity='0.5'/>\
+                 </g>\
+             </svg>",
+            &usvg::Options { keep_named_groups: true, ..usvg::Options::default() },
+        ).unwrap();
+
+        let pixel = |x: usize| {
+            let mut img = backend_skia::Backend.render_to_image(&tree, &Options::default()).unwrap();
+            let data = img.make_rgba_vec();
+            let idx = (10 * 20 + x) * 4;
+            (data[idx], data[idx + 1], data[idx + 2], data[idx + 3])
+        };
+
+        // In a normal group, the blue rect is blended with the red one.
+        let (r, _, b, a) = pixel(10);
+        assert!(r > 100 && r < 155 && b > 100 && b < 155);
+        assert_eq!(a, 255);
+
+        if let usvg::NodeKind::Group(ref mut g) = *tree.node_by_id("g1").unwrap().borrow_mut() {
+            g.knockout = true;
+        }
+
+        // In a knockout group, the blue rect replaces the red one where they overlap.
+        let (r, g, b, a) = pixel(10);
+        assert_eq!((r, g), (0, 0));
+        assert!(b > 250 && a > 120 && a < 135);
+        assert_eq!(pixel(10), pixel(17));
+
+        // Everything else is not affected.
+        assert_eq!(pixel(2), (255, 0, 0, 255));
+    }
+
+    #[test]
+    fn render_depth_limit() {
+        // Groups with a clip path will not be merged by usvg.
+        let tree = usvg::Tree::from_str(
+            "<svg width='20' height='20' xmlns='http://www.w3.org/2000/svg'>\
+                 <clipPath id='clip1'><rect width='20' height='20'/></clipPath>\
+                 <g clip-path='url(#clip1)'><g clip-path='url(#clip1)'>\
+                 <g clip-path='url(#clip1)'><g clip-path='url(#clip1)'>\
+                     <rect width='20' height='20'/>\
+                 </g></g></g></g>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        let center_alpha = |max_render_depth| {
+            let opt = Options { max_render_depth, ..Options::default() };
+            backends().iter().map(|backend| {
+                let mut img = backend.render_to_image(&tree, &opt).unwrap();
+                img.make_rgba_vec()[(10 * 20 + 10) * 4 + 3]
+            }).collect::<Vec<_>>()
+        };
+
+        assert!(center_alpha(256).iter().all(|a| *a == 255));
+        // The root and two groups are rendered, the rest is skipped.
+        assert!(center_alpha(3).iter().all(|a| *a == 0));
+    }
+
+    #[test]
+    fn visibility_override() {
+        let tree = usvg::Tree::from_str(
+            "<svg width='30' height='10' xmlns='http://www.w3.org/2000/svg'>\
+                 <g id='layer1'>\
+                     <rect x='0' width='10' height='10'/>\
+                     <rect x='10' width='10' height='10' visibility='hidden'/>\
+                 </g>\
+                 <g id='layer2' visibility='hidden'>\
+                     <rect x='20' width='10' height='10'/>\
+                 </g>\
+             </svg>",
+            &usvg::Options { keep_named_groups: true, ..usvg::Options::default() },
+        ).unwrap();
+
+        let visible = ["layer1", "layer2"].iter().map(|s| s.to_string()).collect();
+        for backend in backends() {
+            let mut img = render_with_visibility(
+                backend.as_ref(), &tree, &Options::default(), &visible, &Default::default(),
+            ).unwrap();
+
+            let data = img.make_rgba_vec();
+            let alpha = |x: usize| data[(5 * 30 + x) * 4 + 3];
+            assert_eq!(alpha(5), 255);
+            // A hidden child of a visible layer stays hidden.
+            assert_eq!(alpha(15), 0);
+            // A hidden layer is shown.
+            assert_eq!(alpha(25), 255);
+        }
+    }
+
+    #[test]
+    fn merged_fills_seam() {
+        // Two triangles forming a square, split along the diagonal.
+        let tree = usvg::Tree::from_str(
+            "<svg width='20' height='20' xmlns='http://www.w3.org/2000/svg'>\
+                 <path d='M 0 0 L 20 0 L 0 20 Z' fill='green'/>\
+                 <path d='M 20 0 L 20 20 L 0 20 Z' fill='green'/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        let diagonal_alpha = |mut img: Box<dyn OutputImage>| {
+            let data = img.make_rgba_vec();
+            (0..20).map(|x| data[((19 - x) * 20 + x) * 4 + 3]).collect::<Vec<_>>()
+        };
+
+        for backend in backends() {
+            let img = backend.render_to_image(&tree, &Options::default()).unwrap();
+            assert!(diagonal_alpha(img).iter().any(|a| *a < 255));
+
+            let img = render_with_merged_fills(backend.as_ref(), &tree, &Options::default()).unwrap();
+            assert!(diagonal_alpha(img).iter().all(|a| *a == 255));
+        }
+    }
+}
@@ -0,0 +1,154 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::rc::Rc;
+
+use crate::prelude::*;
+
+
+/// Merges adjacent sibling paths with the same solid fill into a single path.
+///
+/// When two antialiased shapes share an edge, pixels along it are blended twice
+/// and the background bleeds through as a thin seam. A single path has its coverage
+/// calculated at once, so there is no seam.
+///
+/// Only `nonzero` paths with bounding boxes that touch at most by an edge are merged,
+/// since overlapping subpaths can change the fill area or the opacity.
+pub fn merge_fills(tree: &usvg::Tree) {
+    let parents: Vec<_> = tree.root().descendants().collect();
+    for parent in parents {
+        let children: Vec<_> = parent.children().collect();
+        let mut prev: Option<usvg::Node> = None;
+        for mut node in children {
+            if let Some(ref mut prev) = prev {
+                if can_merge(prev, &node) {
+                    append_data(prev, &node);
+                    node.detach();
+                    continue;
+                }
+            }
+
+            prev = if is_mergeable(&node) { Some(node) } else { None };
+        }
+    }
+}
+
+fn is_mergeable(node: &usvg::Node) -> bool {
+    if let usvg::NodeKind::Path(ref path) = *node.borrow() {
+        if let Some(ref fill) = path.fill {
+            if let usvg::Paint::Color(_) = fill.paint {
+                return path.stroke.is_none() && path.visibility == usvg::Visibility::Visible;
+            }
+        }
+    }
+
+    false
+}
+
+fn can_merge(prev: &usvg::Node, node: &usvg::Node) -> bool {
+    if !is_mergeable(node) {
+        return false;
+    }
+
+    match (&*prev.borrow(), &*node.borrow()) {
+        (usvg::NodeKind::Path(path1), usvg::NodeKind::Path(path2)) => {
+            let fill1 = path1.fill.as_ref().unwrap();
+            let fill2 = path2.fill.as_ref().unwrap();
+
+            let same_color = match (&fill1.paint, &fill2.paint) {
+                (usvg::Paint::Color(c1), usvg::Paint::Color(c2)) => c1 == c2,
+                _ => false,
+            };
+
+               same_color
+            && fill1.rule == usvg::FillRule::NonZero
+            && fill2.rule == usvg::FillRule::NonZero
+            && fill1.opacity.value().fuzzy_eq(&fill2.opacity.value())
+            && path1.transform.fuzzy_eq(&path2.transform)
+            && path1.rendering_mode == path2.rendering_mode
+            && !is_overlapping(&path1.data, &path2.data)
+        }
+        _ => false,
+    }
+}
+
+/// Checks that the paths' bounding boxes share more than an edge.
+///
+/// Paths without a bounding box are treated as overlapping.
+fn is_overlapping(data1: &usvg::PathData, data2: &usvg::PathData) -> bool {
+    let (r1, r2) = match (data1.bbox(), data2.bbox()) {
+        (Some(r1), Some(r2)) => (r1, r2),
+        _ => return true,
+    };
+
+    let w = r1.right().min(r2.right()) - r1.x().max(r2.x());
+    let h = r1.bottom().min(r2.bottom()) - r1.y().max(r2.y());
+    w > 0.0 && h > 0.0 && !w.is_fuzzy_zero() && !h.is_fuzzy_zero()
+}
+
+fn append_data(prev: &mut usvg::Node, node: &usvg::Node) {
+    let data = match *node.borrow() {
+        usvg::NodeKind::Path(ref path) => path.data.clone(),
+        _ => return,
+    };
+
+    if let usvg::NodeKind::Path(ref mut path) = *prev.borrow_mut() {
+        Rc::make_mut(&mut path.data).extend_from_slice(&data);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjacent_rects() {
+        let tree = usvg::Tree::from_str(
+            "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>\
+                 <path id='path1' d='M 10 10 L 60 10 L 60 110 L 10 110 Z' fill='green'/>\
+                 <path id='path2' d='M 60 10 L 110 10 L 110 110 L 60 110 Z' fill='green'/>\
+                 <path id='path3' d='M 10 120 L 110 120 L 10 190 Z' fill='blue'/>\
+                 <path id='path4' d='M 120 10 L 190 10 L 190 90 Z' fill='green'/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        merge_fills(&tree);
+
+        assert!(tree.node_by_id("path2").is_none());
+        assert!(tree.node_by_id("path3").is_some());
+        assert!(tree.node_by_id("path4").is_some());
+
+        let node = tree.node_by_id("path1").unwrap();
+        if let usvg::NodeKind::Path(ref path) = *node.borrow() {
+            assert_eq!(path.data.len(), 10);
+        } else {
+            unreachable!();
+        };
+    }
+
+    #[test]
+    fn overlapping_and_even_odd() {
+        // The second path overlaps the first one and has an opposite direction,
+        // so merging would punch a hole with `nonzero` or `evenodd`.
+        let tree = usvg::Tree::from_str(
+            "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>\
+                 <path id='path1' d='M 10 10 L 110 10 L 110 110 L 10 110 Z' fill='green'/>\
+                 <path id='path2' d='M 50 50 L 50 150 L 150 150 L 150 50 Z' fill='green'/>\
+                 <path id='path3' d='M 10 160 L 60 160 L 60 190 L 10 190 Z'\
+                       fill='green' fill-rule='evenodd'/>\
+                 <path id='path4' d='M 60 160 L 110 160 L 110 190 L 60 190 Z'\
+                       fill='green' fill-rule='evenodd'/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        merge_fills(&tree);
+
+        for id in &["path1", "path2", "path3", "path4"] {
+            assert!(tree.node_by_id(id).is_some());
+        }
+    }
+}
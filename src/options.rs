@@ -16,6 +16,48 @@ pub enum FitTo {
     Zoom(f32),
 }
 
+/// An image background.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Background {
+    /// A solid color.
+    Color(usvg::Color),
+    /// A vertical linear gradient.
+    LinearGradient {
+        /// A color at the top edge of the image.
+        top: usvg::Color,
+        /// A color at the bottom edge of the image.
+        bottom: usvg::Color,
+    },
+    /// A checkerboard pattern.
+    ///
+    /// Useful to visualize transparency.
+    Checkerboard {
+        /// A cell size in pixels.
+        size: u32,
+        /// A color of the top-left cell.
+        light: usvg::Color,
+        /// A color of the adjacent cells.
+        dark: usvg::Color,
+    },
+}
+
+impl Background {
+    /// Creates a checkerboard with 8px white and light gray cells.
+    pub fn checkerboard() -> Self {
+        Background::Checkerboard {
+            size: 8,
+            light: usvg::Color::new(255, 255, 255),
+            dark: usvg::Color::new(204, 204, 204),
+        }
+    }
+}
+
+impl From<usvg::Color> for Background {
+    fn from(c: usvg::Color) -> Self {
+        Background::Color(c)
+    }
+}
+
 /// Rendering options.
 pub struct Options {
     /// `usvg` preprocessor options.
@@ -26,12 +68,54 @@ pub struct Options {
     /// Does not affect rendering to canvas.
     pub fit_to: FitTo,
 
-    /// An image background color.
+    /// An image background.
+    ///
+    /// Sets an image background. Does not affect rendering to canvas.
+    ///
+    /// `None` equals to transparent, i.e. all pixels that were not painted
+    /// are guaranteed to be `(0, 0, 0, 0)` with all backends.
+    pub background: Option<Background>,
+
+    /// Blend in a linear RGB color space.
     ///
-    /// Sets an image background color. Does not affect rendering to canvas.
+    /// Produces more natural antialiased edges between different colors,
+    /// but deviates from the SVG spec, which requires blending in sRGB.
+    /// Intermediate images are still 8 bit, so dark gradients may have visible banding.
     ///
-    /// `None` equals to transparent.
-    pub background: Option<usvg::Color>,
+    /// Supported only by the Skia backend. Does not affect rendering to canvas.
+    ///
+    /// Default: false
+    pub linear_compositing: bool,
+
+    /// A supersampling factor.
+    ///
+    /// Renders an image in a `supersample` times higher resolution and then downscales it
+    /// using a box filter. Improves antialiasing of thin strokes and text.
+    /// `1` disables supersampling. Will be clamped to the 1..4 range,
+    /// since memory usage grows quadratically.
+    ///
+    /// Supported only by the Skia backend. Does not affect rendering to canvas.
+    ///
+    /// Default: 1
+    pub supersample: u32,
+
+    /// A maximum render nesting depth.
+    ///
+    /// Groups nested deeper than this value will be skipped with a warning.
+    /// Prevents stack overflows caused by malicious files.
+    /// Patterns, clip paths, masks and filters count as a nesting level too.
+    ///
+    /// Default: 256
+    pub max_render_depth: u32,
+
+    /// A maximum filter region size in pixels.
+    ///
+    /// A filter region larger than this value in any dimension will be clipped
+    /// around its center with a warning.
+    /// Prevents huge allocations caused by malicious files rendered to a large canvas.
+    ///
+    /// Default: 8192
+    pub max_filter_region: u32,
 }
 
 impl Default for Options {
@@ -40,6 +124,18 @@ impl Default for Options {
             usvg: usvg::Options::default(),
             fit_to: FitTo::Original,
             background: None,
+            linear_compositing: false,
+            supersample: 1,
+            max_render_depth: 256,
+            max_filter_region: 8192,
         }
     }
 }
+
+impl Options {
+    /// Returns a clamped supersampling factor.
+    #[cfg(feature = "skia-backend")]
+    pub(crate) fn supersample_factor(&self) -> u32 {
+        std::cmp::min(std::cmp::max(self.supersample, 1), 4)
+    }
+}
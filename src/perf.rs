@@ -0,0 +1,148 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Per-phase rendering timings.
+//!
+//! Timings are collected only after `set_enabled(true)` was called
+//! and only for the current thread.
+//!
+//! When the `tracing` feature is enabled, rendering phases are also reported
+//! as `tracing` spans at the `INFO` level, regardless of `set_enabled`:
+//!
+//! - `parse` - SVG parsing by `render_from_reader`.
+//!   Fields: `nodes` - the number of nodes in the parsed tree, `elapsed_us`.
+//! - `render` - rendering of a tree or a node by `Render` methods.
+//!   Fields: `nodes` - the number of nodes to render, including `defs`, `elapsed_us`.
+//! - `filter`, `clip_path` and `mask` - applying of a filter, a clip path or a mask.
+//!   Fields: `elapsed_us`.
+//!
+//! `elapsed_us` is the phase duration in microseconds. Spans are nested,
+//! so a `mask` span will be inside a `render` span.
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+
+thread_local! {
+    static ENABLED: Cell<bool> = Cell::new(false);
+    static STATS: Cell<Stats> = Cell::new(Stats::default());
+}
+
+/// Accumulated rendering timings.
+///
+/// Nested phases are counted in both, so a filter inside a mask
+/// will be included in `filters` and `masks` at the same time.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Stats {
+    /// Time spent applying filters.
+    pub filters: Duration,
+    /// Time spent applying clip paths.
+    pub clip_paths: Duration,
+    /// Time spent applying masks.
+    pub masks: Duration,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Phase {
+    Filter,
+    ClipPath,
+    Mask,
+}
+
+/// Enables or disables timings collection.
+pub fn set_enabled(flag: bool) {
+    ENABLED.with(|e| e.set(flag));
+}
+
+/// Returns accumulated timings and resets them.
+pub fn take_stats() -> Stats {
+    STATS.with(|s| s.replace(Stats::default()))
+}
+
+/// Runs `f` and adds its execution time to the specified phase.
+pub(crate) fn measure<T, F: FnOnce() -> T>(phase: Phase, f: F) -> T {
+    #[cfg(feature = "tracing")]
+    let f = move || {
+        let span = match phase {
+            Phase::Filter => tracing::info_span!("filter", elapsed_us = tracing::field::Empty),
+            Phase::ClipPath => tracing::info_span!("clip_path", elapsed_us = tracing::field::Empty),
+            Phase::Mask => tracing::info_span!("mask", elapsed_us = tracing::field::Empty),
+        };
+        in_span(&span, f)
+    };
+
+    if !ENABLED.with(|e| e.get()) {
+        return f();
+    }
+
+    let now = Instant::now();
+    let res = f();
+    let elapsed = now.elapsed();
+
+    STATS.with(|s| {
+        let mut stats = s.get();
+        match phase {
+            Phase::Filter => stats.filters += elapsed,
+            Phase::ClipPath => stats.clip_paths += elapsed,
+            Phase::Mask => stats.masks += elapsed,
+        }
+        s.set(stats);
+    });
+
+    res
+}
+
+/// Runs `f` inside a `render` span.
+#[cfg(feature = "tracing")]
+pub(crate) fn trace_render<T, F: FnOnce() -> T>(node: &usvg::Node, f: F) -> T {
+    let span = tracing::info_span!(
+        "render",
+        nodes = node.descendants().count() as u64,
+        elapsed_us = tracing::field::Empty,
+    );
+    in_span(&span, f)
+}
+
+/// Runs `f` inside a `render` span.
+#[cfg(not(feature = "tracing"))]
+#[inline]
+pub(crate) fn trace_render<T, F: FnOnce() -> T>(_: &usvg::Node, f: F) -> T {
+    f()
+}
+
+/// Runs `f` inside a `parse` span.
+#[cfg(feature = "tracing")]
+pub(crate) fn trace_parse<F>(f: F) -> Result<usvg::Tree, usvg::Error>
+    where F: FnOnce() -> Result<usvg::Tree, usvg::Error>
+{
+    let span = tracing::info_span!(
+        "parse",
+        nodes = tracing::field::Empty,
+        elapsed_us = tracing::field::Empty,
+    );
+    let res = in_span(&span, f);
+    if let Ok(ref tree) = res {
+        span.record("nodes", &(tree.root().descendants().count() as u64));
+    }
+
+    res
+}
+
+/// Runs `f` inside a `parse` span.
+#[cfg(not(feature = "tracing"))]
+#[inline]
+pub(crate) fn trace_parse<F>(f: F) -> Result<usvg::Tree, usvg::Error>
+    where F: FnOnce() -> Result<usvg::Tree, usvg::Error>
+{
+    f()
+}
+
+#[cfg(feature = "tracing")]
+fn in_span<T, F: FnOnce() -> T>(span: &tracing::Span, f: F) -> T {
+    let _guard = span.enter();
+    let now = Instant::now();
+    let res = f();
+    span.record("elapsed_us", &(now.elapsed().as_micros() as u64));
+    res
+}
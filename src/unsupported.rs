@@ -0,0 +1,115 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::prelude::*;
+
+
+/// A rendering backend kind.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BackendKind {
+    /// The cairo backend.
+    Cairo,
+    /// The Qt backend.
+    Qt,
+    /// The raqote backend.
+    Raqote,
+    /// The Skia backend.
+    Skia,
+}
+
+/// Returns features used by the `tree` that will not be rendered correctly by the `backend`.
+///
+/// Unlike `docs/unsupported.md`, which lists SVG features ignored by `usvg` during parsing,
+/// this function reports only limitations of the specified backend and build features.
+/// The list is conservative, so an empty result doesn't guarantee a perfect render.
+///
+/// Each feature is reported once, using one of the following identifiers:
+///
+/// - `png-image` - a PNG image, when `resvg` was built without the `png` feature
+/// - `feTurbulence-complex-transform` - an `feTurbulence` filter primitive
+///   with a skew or rotation, when rendering with the Qt backend
+pub fn unsupported_features(tree: &usvg::Tree, backend: BackendKind) -> Vec<String> {
+    let mut features = Vec::new();
+    let mut add = |name: &str| {
+        if !features.iter().any(|f| f == name) {
+            features.push(name.to_string());
+        }
+    };
+
+    for node in tree.root().descendants() {
+        if tree.is_in_defs(&node) {
+            continue;
+        }
+
+        match *node.borrow() {
+            usvg::NodeKind::Image(ref img) => {
+                if !cfg!(feature = "png") && img.format == usvg::ImageFormat::PNG {
+                    add("png-image");
+                }
+            }
+            usvg::NodeKind::Group(ref g) => {
+                if backend != BackendKind::Qt {
+                    continue;
+                }
+
+                let filter = match g.filter.as_ref().and_then(|id| tree.defs_by_id(id)) {
+                    Some(filter) => filter,
+                    None => continue,
+                };
+
+                let has_turbulence = match *filter.borrow() {
+                    usvg::NodeKind::Filter(ref filter) => {
+                        filter.children.iter().any(|c| {
+                            if let usvg::FilterKind::FeTurbulence(..) = c.kind {
+                                true
+                            } else {
+                                false
+                            }
+                        })
+                    }
+                    _ => false,
+                };
+
+                if has_turbulence {
+                    let mut ts = node.abs_transform();
+                    ts.append(&g.transform);
+                    if ts.has_skew() || ts.has_rotate() {
+                        add("feTurbulence-complex-transform");
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    features
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backend_limitations() {
+        let tree = usvg::Tree::from_str(
+            "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg' \
+                  xmlns:xlink='http://www.w3.org/1999/xlink'>\
+                 <filter id='filter1'><feTurbulence baseFrequency='0.01'/></filter>\
+                 <rect width='20' height='20' filter='url(#filter1)' transform='rotate(30)'/>\
+                 <rect width='20' height='20' filter='url(#filter1)' transform='scale(2)'/>\
+                 <image width='1' height='1' xlink:href='data:image/png;base64,\
+                     iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg=='/>\
+             </svg>",
+            &usvg::Options::default(),
+        ).unwrap();
+
+        let features = unsupported_features(&tree, BackendKind::Qt);
+        assert_eq!(features.iter().any(|f| f == "png-image"), !cfg!(feature = "png"));
+        assert!(features.iter().any(|f| f == "feTurbulence-complex-transform"));
+
+        let features = unsupported_features(&tree, BackendKind::Skia);
+        assert!(!features.iter().any(|f| f == "feTurbulence-complex-transform"));
+    }
+}
@@ -36,6 +36,26 @@ pub(crate) fn fit_to(
     }
 }
 
+/// Returns a `FitTo` that fits `size` into a `max_width` x `max_height` box.
+pub(crate) fn fit_within(
+    size: ScreenSize,
+    max_width: u32,
+    max_height: u32,
+    allow_upscale: bool,
+) -> FitTo {
+    let (w, h) = size.dimensions();
+    if !allow_upscale && w <= max_width && h <= max_height {
+        return FitTo::Original;
+    }
+
+    // Compare `w / max_width` and `h / max_height` without division.
+    if w as u64 * max_height as u64 >= h as u64 * max_width as u64 {
+        FitTo::Width(max_width)
+    } else {
+        FitTo::Height(max_height)
+    }
+}
+
 pub(crate) fn apply_view_box(
     vb: &usvg::ViewBox,
     img_size: ScreenSize,
@@ -53,3 +73,360 @@ pub(crate) fn apply_view_box(
     }
 }
 
+
+/// Calculates node's absolute visual bounding box.
+///
+/// Unlike `usvg::NodeExt::calculate_bbox`, which returns a geometric bounding box
+/// (with an approximated stroke), also takes filter regions into account,
+/// since a filter can paint outside the element geometry.
+///
+/// Strokes are included using `usvg::PathData::stroke_bbox_with_transform`,
+/// so a stroke is never cut off, even on a scaled or a stroke-only element.
+/// But `objectBoundingBox` filter regions are resolved using the geometric bbox,
+/// just like during rendering.
+pub fn node_visual_bbox(
+    node: &usvg::Node,
+) -> Option<Rect> {
+    calc_visual_bbox(node, node.abs_transform())
+}
+
+fn calc_visual_bbox(
+    node: &usvg::Node,
+    ts: usvg::Transform,
+) -> Option<Rect> {
+    let mut ts2 = ts;
+    ts2.append(&node.transform());
+
+    match *node.borrow() {
+        usvg::NodeKind::Path(ref path) => {
+            match path.stroke {
+                Some(ref stroke) => path.data.stroke_bbox_with_transform(ts2, stroke),
+                None => path.data.bbox_with_transform(ts2, None),
+            }
+        }
+        usvg::NodeKind::Image(ref img) => {
+            let path = usvg::PathData::from_rect(img.view_box.rect);
+            path.bbox_with_transform(ts2, None)
+        }
+        usvg::NodeKind::Svg(_) => {
+            calc_children_bbox(node, ts2)
+        }
+        usvg::NodeKind::Group(ref g) => {
+            let filter = g.filter.as_ref().and_then(|id| node.tree().defs_by_id(id));
+            if let Some(filter) = filter {
+                if let usvg::NodeKind::Filter(ref filter) = *filter.borrow() {
+                    // Filter region clips the content, so it can be used as is.
+                    let region = if filter.units == usvg::Units::ObjectBoundingBox {
+                        let bbox = calc_geometric_children_bbox(node, usvg::Transform::default())?;
+                        filter.rect.bbox_transform(bbox)
+                    } else {
+                        filter.rect
+                    };
+
+                    return region.transform(&ts2);
+                }
+            }
+
+            calc_children_bbox(node, ts2)
+        }
+        _ => None,
+    }
+}
+
+fn calc_children_bbox(
+    node: &usvg::Node,
+    ts: usvg::Transform,
+) -> Option<Rect> {
+    let mut bbox = Rect::new_bbox();
+    for child in node.children() {
+        if let Some(c_bbox) = calc_visual_bbox(&child, ts) {
+            bbox = bbox.expand(c_bbox);
+        }
+    }
+
+    // Check that bbox was changed, otherwise we will have a rect with x/y set to f64::MAX.
+    if bbox.fuzzy_ne(&Rect::new_bbox()) {
+        Some(bbox)
+    } else {
+        None
+    }
+}
+
+/// Calculates children bbox the same way the renderer does
+/// to resolve `objectBoundingBox` units, i.e. without strokes and filter regions.
+fn calc_geometric_children_bbox(
+    node: &usvg::Node,
+    ts: usvg::Transform,
+) -> Option<Rect> {
+    let mut bbox = Rect::new_bbox();
+    for child in node.children() {
+        let mut ts2 = ts;
+        ts2.append(&child.transform());
+
+        let c_bbox = match *child.borrow() {
+            usvg::NodeKind::Path(ref path) => {
+                path.data.bbox_with_transform(ts2, None)
+            }
+            usvg::NodeKind::Image(ref img) => {
+                let path = usvg::PathData::from_rect(img.view_box.rect);
+                path.bbox_with_transform(ts2, None)
+            }
+            usvg::NodeKind::Group(_) => {
+                calc_geometric_children_bbox(&child, ts2)
+            }
+            _ => None,
+        };
+
+        if let Some(c_bbox) = c_bbox {
+            bbox = bbox.expand(c_bbox);
+        }
+    }
+
+    if bbox.fuzzy_ne(&Rect::new_bbox()) {
+        Some(bbox)
+    } else {
+        None
+    }
+}
+
+/// Converts an RGBA image into a packed 1-bit alpha mask.
+///
+/// A bit is set when a pixel alpha is greater than or equal to the `threshold`.
+/// The most significant bit of a byte represents the leftmost pixel.
+/// Each row is padded to a whole byte, i.e. occupies `(width + 7) / 8` bytes.
+pub fn alpha_mask(
+    rgba: &[u8],
+    width: usize,
+    threshold: u8,
+) -> Vec<u8> {
+    if width == 0 {
+        return Vec::new();
+    }
+
+    let stride = (width + 7) / 8;
+    let height = rgba.len() / 4 / width;
+    let mut mask = vec![0; stride * height];
+    for (i, pixel) in rgba.chunks(4).take(width * height).enumerate() {
+        if pixel[3] >= threshold {
+            let (x, y) = (i % width, i / width);
+            mask[y * stride + x / 8] |= 0x80 >> (x % 8);
+        }
+    }
+
+    mask
+}
+
+/// Returns an iterator over RGBA image rows, from top to bottom.
+///
+/// Each row is a `width * 4` bytes slice. Data is expected to be tightly packed,
+/// without any row padding, like the one returned by `OutputImage::make_rgba_vec`.
+/// A trailing incomplete row is ignored.
+///
+/// Useful for streaming encoders that consume scanlines.
+///
+/// # Panics
+///
+/// When `width` is zero.
+pub fn rgba_rows(
+    rgba: &[u8],
+    width: usize,
+) -> std::slice::ChunksExact<u8> {
+    rgba.chunks_exact(width * 4)
+}
+
+/// Converts RGBA pixels between a straight and a premultiplied alpha.
+///
+/// Does nothing when `from` and `to` are the same.
+pub fn convert_pixel_format(
+    rgba: &mut [u8],
+    from: PixelFormat,
+    to: PixelFormat,
+) {
+    use rgb::FromSlice;
+
+    // The alpha channel is the last one, so RGBA can be processed as BGRA.
+    match (from, to) {
+        (PixelFormat::RgbaStraight, PixelFormat::RgbaPremultiplied) => {
+            svgfilters::multiply_alpha(rgba.as_bgra_mut());
+        }
+        (PixelFormat::RgbaPremultiplied, PixelFormat::RgbaStraight) => {
+            svgfilters::demultiply_alpha(rgba.as_bgra_mut());
+        }
+        _ => {}
+    }
+}
+
+/// Inserts an `sRGB` chunk with a perceptual rendering intent into PNG data.
+///
+/// Does nothing when the data already has an `sRGB` or `iCCP` chunk,
+/// since they are mutually exclusive.
+///
+/// Returns `false` when `data` is not a valid PNG.
+pub fn add_png_srgb_chunk(data: &mut Vec<u8>) -> bool {
+    const SIGNATURE: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+    if !data.starts_with(SIGNATURE) {
+        return false;
+    }
+
+    // Collect chunk types and find the end of `IHDR`, which must be the first chunk.
+    let mut ihdr_end = None;
+    let mut pos = SIGNATURE.len();
+    while pos + 12 <= data.len() {
+        let len = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
+        let kind = &data[pos + 4..pos + 8];
+        if kind == b"sRGB" || kind == b"iCCP" {
+            return true;
+        }
+
+        let end = pos + 12 + len as usize;
+        if kind == b"IHDR" && pos == SIGNATURE.len() {
+            ihdr_end = Some(end);
+        }
+
+        pos = end;
+    }
+
+    let ihdr_end = match ihdr_end {
+        Some(end) if end <= data.len() => end,
+        _ => return false,
+    };
+
+    let mut chunk = Vec::with_capacity(13);
+    chunk.extend_from_slice(&1u32.to_be_bytes());
+    chunk.extend_from_slice(b"sRGB");
+    chunk.push(0);
+    let crc = crc32(&chunk[4..]);
+    chunk.extend_from_slice(&crc.to_be_bytes());
+
+    let tail = data.split_off(ihdr_end);
+    data.extend_from_slice(&chunk);
+    data.extend_from_slice(&tail);
+    true
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for b in data {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn png_srgb_chunk() {
+        // A 1x1 RGBA image.
+        let mut data = vec![
+            0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A,
+            0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44, 0x52,
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00,
+            0x1F, 0x15, 0xC4, 0x89,
+            0x00, 0x00, 0x00, 0x0D, 0x49, 0x44, 0x41, 0x54,
+            0x78, 0xDA, 0x63, 0x64, 0xF8, 0xCF, 0x50, 0x0F, 0x00, 0x03, 0x86, 0x01, 0x80,
+            0x5A, 0x34, 0x7D, 0x6B,
+            0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4E, 0x44, 0xAE, 0x42, 0x60, 0x82,
+        ];
+        let len = data.len();
+
+        assert!(add_png_srgb_chunk(&mut data));
+        assert_eq!(data.len(), len + 13);
+        assert_eq!(&data[33..46], &[
+            0x00, 0x00, 0x00, 0x01, 0x73, 0x52, 0x47, 0x42, 0x00, 0xAE, 0xCE, 0x1C, 0xE9,
+        ]);
+
+        // Already tagged.
+        assert!(add_png_srgb_chunk(&mut data));
+        assert_eq!(data.len(), len + 13);
+
+        assert!(!add_png_srgb_chunk(&mut vec![0; 16]));
+    }
+
+    #[test]
+    fn fit_within_box() {
+        let size = ScreenSize::new(200, 100).unwrap();
+        assert_eq!(fit_within(size, 512, 512, false), FitTo::Original);
+        assert_eq!(fit_within(size, 512, 512, true), FitTo::Width(512));
+        assert_eq!(fit_within(size, 100, 100, false), FitTo::Width(100));
+        assert_eq!(fit_within(size, 400, 20, false), FitTo::Height(20));
+
+        for &(max_w, max_h) in &[(100, 100), (400, 20), (33, 17), (512, 512)] {
+            let fit = fit_within(size, max_w, max_h, true);
+            let (w, h) = fit_to(size, fit).unwrap().dimensions();
+            assert!(w <= max_w && h <= max_h);
+        }
+    }
+
+    #[test]
+    fn pixel_format_round_trip() {
+        let straight = vec![
+            255, 0, 0, 255,
+            255, 255, 255, 128,
+            0, 0, 0, 0,
+            0, 255, 0, 51,
+        ];
+
+        let mut data = straight.clone();
+        convert_pixel_format(&mut data, PixelFormat::RgbaStraight, PixelFormat::RgbaPremultiplied);
+        assert_eq!(data, vec![
+            255, 0, 0, 255,
+            128, 128, 128, 128,
+            0, 0, 0, 0,
+            0, 51, 0, 51,
+        ]);
+
+        convert_pixel_format(&mut data, PixelFormat::RgbaPremultiplied, PixelFormat::RgbaStraight);
+        assert_eq!(data, straight);
+    }
+
+    #[test]
+    fn alpha_mask_soft_edge() {
+        // Two rows of a soft edge, 9 pixels each.
+        let alphas = [0, 64, 127, 128, 200, 255, 255, 128, 10,
+                      255, 200, 128, 127, 64, 0, 0, 0, 128];
+        let mut rgba = Vec::new();
+        for a in alphas.iter() {
+            rgba.extend_from_slice(&[0, 0, 0, *a]);
+        }
+
+        let mask = alpha_mask(&rgba, 9, 128);
+        assert_eq!(mask, vec![0b0001_1111, 0b0000_0000, 0b1110_0000, 0b1000_0000]);
+    }
+
+    #[test]
+    fn rgba_rows_split() {
+        let data: Vec<u8> = (0..24).collect();
+        let rows: Vec<&[u8]> = rgba_rows(&data, 2).collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1], &[8, 9, 10, 11, 12, 13, 14, 15][..]);
+    }
+
+    #[test]
+    fn visual_bbox_of_stroked_filter() {
+        // Filter units are resolved using the geometric bbox, i.e. without the stroke.
+        let tree = usvg::Tree::from_str(
+            "<svg width='100' height='100' xmlns='http://www.w3.org/2000/svg'>\
+                 <filter id='filter1'>\
+                     <feOffset/>\
+                 </filter>\
+                 <g id='g1' filter='url(#filter1)'>\
+                     <rect x='10' y='10' width='20' height='20' stroke='black' stroke-width='10'/>\
+                 </g>\
+             </svg>",
+            &usvg::Options { keep_named_groups: true, ..usvg::Options::default() },
+        ).unwrap();
+
+        let node = tree.node_by_id("g1").unwrap();
+        let bbox = node_visual_bbox(&node).unwrap();
+        assert!(bbox.fuzzy_eq(&Rect::new(8.0, 8.0, 24.0, 24.0).unwrap()));
+    }
+}
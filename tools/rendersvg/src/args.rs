@@ -36,7 +36,8 @@ OPTIONS:
                                 [default: 96] [possible values: 10..4000]
 
         --background COLOR      Sets the background color.
-                                Examples: red, #fff, #fff000
+                                Use 'checkerboard' to visualize transparency.
+                                Examples: red, #fff, #fff000, checkerboard
         --font-family FAMILY    Sets the default font family
                                 [default: 'Times New Roman']
         --font-size SIZE        Sets the default font size
@@ -58,15 +59,33 @@ OPTIONS:
                                 [default: optimizeQuality]
                                 [possible values: optimizeQuality,
                                 optimizeSpeed]
+        --linear-compositing    Blends in a linear RGB color space.
+                                Deviates from the SVG spec.
+                                Supported only by the Skia backend
+        --supersample FACTOR    Renders in a higher resolution and downscales
+                                the result. Supported only by the Skia backend
+                                [default: 1] [possible values: 1..4]
 
         --query-all             Queries all valid SVG ids with bounding boxes
         --export-id ID          Renders an object only with a specified ID
 
-        --perf                  Prints performance stats
+        --perf                  Prints performance stats,
+                                including filters, clipping and masking time
         --pretend               Does all the steps except rendering
+        --watch                 Re-renders the image each time the input file
+                                changes. Requires the 'watch' build feature
         --quiet                 Disables warnings
         --dump-svg PATH         Saves the preprocessed SVG to the selected file
 
+        --compare PNG           Compares the rendered image with a reference PNG
+                                and fails if they differ too much
+        --diff-threshold PERCENT
+                                Sets the maximum allowed percentage
+                                of differing pixels. Used with --compare
+                                [default: 0] [possible values: 0..100]
+        --diff-out PATH         Saves the differing pixels as a PNG.
+                                Used with --compare
+
 ARGS:
     <in-svg>                    Input file
     <out-png>                   Output file
@@ -83,19 +102,25 @@ struct CliArgs {
     height: Option<u32>,
     zoom: Option<f32>,
     dpi: u32,
-    background: Option<usvg::Color>,
+    background: Option<Background>,
     font_family: String,
     font_size: u32,
     languages: Vec<String>,
     shape_rendering: usvg::ShapeRendering,
     text_rendering: usvg::TextRendering,
     image_rendering: usvg::ImageRendering,
+    linear_compositing: bool,
+    supersample: u32,
     query_all: bool,
     export_id: Option<String>,
     perf: bool,
     pretend: bool,
+    watch: bool,
     quiet: bool,
     dump_svg: Option<String>,
+    compare: Option<String>,
+    diff_threshold: f64,
+    diff_out: Option<String>,
     free: Vec<String>,
 }
 
@@ -109,7 +134,7 @@ fn collect_args() -> Result<CliArgs, pico_args::Error> {
         height:             input.value_from_fn(["-h", "--height"], parse_length)?,
         zoom:               input.value_from_fn(["-z", "--zoom"], parse_zoom)?,
         dpi:                input.value_from_fn("--dpi", parse_dpi)?.unwrap_or(96),
-        background:         input.value_from_str("--background")?,
+        background:         input.value_from_fn("--background", parse_background)?,
         font_family:        input.value_from_str("--font-family")?
                                  .unwrap_or_else(|| "Times New Roman".to_string()),
         font_size:          input.value_from_fn("--font-size", parse_font_size)?.unwrap_or(12),
@@ -118,12 +143,19 @@ fn collect_args() -> Result<CliArgs, pico_args::Error> {
         shape_rendering:    input.value_from_str("--shape-rendering")?.unwrap_or_default(),
         text_rendering:     input.value_from_str("--text-rendering")?.unwrap_or_default(),
         image_rendering:    input.value_from_str("--image-rendering")?.unwrap_or_default(),
+        linear_compositing: input.contains("--linear-compositing"),
+        supersample:        input.value_from_fn("--supersample", parse_supersample)?.unwrap_or(1),
         query_all:          input.contains("--query-all"),
         export_id:          input.value_from_str("--export-id")?,
         perf:               input.contains("--perf"),
         pretend:            input.contains("--pretend"),
+        watch:              input.contains("--watch"),
         quiet:              input.contains("--quiet"),
         dump_svg:           input.value_from_str("--dump-svg")?,
+        compare:            input.value_from_str("--compare")?,
+        diff_threshold:     input.value_from_fn("--diff-threshold", parse_diff_threshold)?
+                                 .unwrap_or(0.0),
+        diff_out:           input.value_from_str("--diff-out")?,
         free:               input.free()?,
     })
 }
@@ -168,6 +200,35 @@ fn parse_font_size(s: &str) -> Result<u32, String> {
     }
 }
 
+fn parse_supersample(s: &str) -> Result<u32, String> {
+    let n: u32 = s.parse().map_err(|_| "invalid number")?;
+
+    if n >= 1 && n <= 4 {
+        Ok(n)
+    } else {
+        Err("supersample factor out of bounds".to_string())
+    }
+}
+
+fn parse_diff_threshold(s: &str) -> Result<f64, String> {
+    let n: f64 = s.parse().map_err(|_| "invalid number")?;
+
+    if n >= 0.0 && n <= 100.0 {
+        Ok(n)
+    } else {
+        Err("diff threshold out of bounds".to_string())
+    }
+}
+
+fn parse_background(s: &str) -> Result<Background, String> {
+    if s == "checkerboard" {
+        return Ok(Background::checkerboard());
+    }
+
+    let c: usvg::Color = s.parse().map_err(|_| "invalid color")?;
+    Ok(Background::Color(c))
+}
+
 fn parse_languages(s: &str) -> Result<Vec<String>, String> {
     let mut langs = Vec::new();
     for lang in s.split(',') {
@@ -189,8 +250,12 @@ pub struct Args {
     pub export_id: Option<String>,
     pub dump: Option<path::PathBuf>,
     pub pretend: bool,
+    pub watch: bool,
     pub perf: bool,
     pub quiet: bool,
+    pub compare: Option<path::PathBuf>,
+    pub diff_threshold: f64,
+    pub diff_out: Option<path::PathBuf>,
 }
 
 pub fn parse() -> Result<(Args, resvg::Options), String> {
@@ -223,6 +288,14 @@ pub fn parse() -> Result<(Args, resvg::Options), String> {
     let dump = args.dump_svg.map(|v| v.into());
     let export_id = args.export_id.map(|v| v.to_string());
 
+    if args.compare.is_none() && args.diff_out.is_some() {
+        return Err("--diff-out requires --compare".to_string());
+    }
+
+    if args.watch && !cfg!(feature = "watch") {
+        return Err("rendersvg has been built without the watch support".to_string());
+    }
+
     let app_args = Args {
         in_svg: in_svg.clone(),
         out_png,
@@ -231,8 +304,12 @@ pub fn parse() -> Result<(Args, resvg::Options), String> {
         export_id,
         dump,
         pretend: args.pretend,
+        watch: args.watch,
         perf: args.perf,
         quiet: args.quiet,
+        compare: args.compare.map(|v| v.into()),
+        diff_threshold: args.diff_threshold,
+        diff_out: args.diff_out.map(|v| v.into()),
     };
 
     // We don't have to keep named groups when we don't need them
@@ -259,9 +336,16 @@ pub fn parse() -> Result<(Args, resvg::Options), String> {
             text_rendering: args.text_rendering,
             image_rendering: args.image_rendering,
             keep_named_groups,
+            image_href_resolver: None,
+            allow_external_resources: true,
+            fallback_paint: None,
         },
         fit_to,
         background: args.background,
+        linear_compositing: args.linear_compositing,
+        supersample: args.supersample,
+        max_render_depth: 256,
+        max_filter_region: 8192,
     };
 
     Ok((app_args, opt))
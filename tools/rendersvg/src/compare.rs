@@ -0,0 +1,112 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fs;
+use std::path;
+
+use resvg::prelude::*;
+
+
+/// Compares a rendered image with a reference PNG.
+///
+/// Returns an error when images have different sizes
+/// or when the percentage of differing pixels is above the `threshold`.
+pub fn compare(
+    img: &mut dyn OutputImage,
+    reference: &path::Path,
+    threshold: f64,
+    diff_out: Option<&path::Path>,
+) -> Result<(), String> {
+    let (ref_size, ref_data) = load_png(reference)?;
+
+    let size = img.size();
+    if size != ref_size {
+        return Err(format!(
+            "image size mismatch: {}x{} vs {}x{}",
+            size.width(), size.height(), ref_size.width(), ref_size.height()
+        ));
+    }
+
+    let data = img.make_rgba_vec();
+
+    let mut diff_data = vec![0; data.len()];
+    let mut pixels = 0;
+    let mut max_delta = 0;
+    for ((c1, c2), d) in data.chunks(4).zip(ref_data.chunks(4)).zip(diff_data.chunks_mut(4)) {
+        let delta = c1.iter().zip(c2).map(|(a, b)| (*a as i16 - *b as i16).abs() as u8).max();
+        let delta = delta.unwrap_or(0);
+
+        if delta != 0 {
+            pixels += 1;
+            max_delta = std::cmp::max(max_delta, delta);
+            d.copy_from_slice(&[255, 0, 0, 255]);
+        }
+    }
+
+    let total = size.width() as u64 * size.height() as u64;
+    let percent = pixels as f64 / total as f64 * 100.0;
+
+    println!("Different pixels: {} ({:.2}%)", pixels, percent);
+    println!("Max channel delta: {}", max_delta);
+
+    if let Some(path) = diff_out {
+        save_png(path, size, &diff_data)?;
+    }
+
+    if percent > threshold {
+        return Err(format!(
+            "images differ by {:.2}%, which is above the {}% threshold", percent, threshold
+        ));
+    }
+
+    Ok(())
+}
+
+fn load_png(path: &path::Path) -> Result<(ScreenSize, Vec<u8>), String> {
+    let file = fs::File::open(path).map_err(|_| format!("failed to open a file {:?}", path))?;
+
+    let mut decoder = png::Decoder::new(file);
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let (info, mut reader) = decoder.read_info()
+        .map_err(|_| format!("failed to decode a file {:?}", path))?;
+
+    if info.bit_depth != png::BitDepth::Eight {
+        return Err(format!("{:?} is not an 8-bit PNG", path));
+    }
+
+    let size = ScreenSize::new(info.width, info.height)
+        .ok_or_else(|| format!("{:?} has an invalid size", path))?;
+
+    let mut data = vec![0; info.buffer_size()];
+    reader.next_frame(&mut data).map_err(|_| format!("failed to decode a file {:?}", path))?;
+
+    let data = match info.color_type {
+        png::ColorType::RGBA => data,
+        png::ColorType::RGB => {
+            data.chunks(3).flat_map(|c| vec![c[0], c[1], c[2], 255]).collect()
+        }
+        png::ColorType::Grayscale => {
+            data.iter().flat_map(|g| vec![*g, *g, *g, 255]).collect()
+        }
+        png::ColorType::GrayscaleAlpha => {
+            data.chunks(2).flat_map(|c| vec![c[0], c[0], c[0], c[1]]).collect()
+        }
+        png::ColorType::Indexed => {
+            return Err(format!("{:?} is an indexed PNG", path));
+        }
+    };
+
+    Ok((size, data))
+}
+
+fn save_png(path: &path::Path, size: ScreenSize, data: &[u8]) -> Result<(), String> {
+    let file = fs::File::create(path).map_err(|_| format!("failed to create a file {:?}", path))?;
+
+    let mut encoder = png::Encoder::new(file, size.width(), size.height());
+    encoder.set_color(png::ColorType::RGBA);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()
+        .and_then(|mut w| w.write_image_data(data))
+        .map_err(|_| format!("failed to write a file {:?}", path))
+}
@@ -10,6 +10,7 @@ use std::path;
 use resvg::prelude::*;
 
 mod args;
+mod compare;
 
 
 macro_rules! bail {
@@ -71,6 +72,16 @@ fn process() -> Result<(), String> {
         _ => bail!("unknown backend"),
     };
 
+    run(&args, &opt, &*backend)?;
+
+    if args.watch {
+        watch(&args, &opt, &*backend)?;
+    }
+
+    Ok(())
+}
+
+fn run(args: &args::Args, opt: &resvg::Options, backend: &dyn Render) -> Result<(), String> {
     macro_rules! timed {
         ($name:expr, $task:expr) => { run_task(args.perf, $name, || $task) };
     }
@@ -95,25 +106,97 @@ fn process() -> Result<(), String> {
 
     // Render.
     if let Some(ref out_png) = args.out_png {
+        resvg::perf::set_enabled(args.perf);
+
         let img = if let Some(ref id) = args.export_id {
             if let Some(node) = tree.root().descendants().find(|n| &*n.id() == id) {
-                timed!("Rendering", backend.render_node_to_image(&node, &opt))
+                timed!("Rendering", backend.render_node_to_image(&node, opt))
             } else {
                 bail!("SVG doesn't have '{}' ID", id)
             }
         } else {
-            timed!("Rendering", backend.render_to_image(&tree, &opt))
+            timed!("Rendering", backend.render_to_image(&tree, opt))
         };
 
-        match img {
-            Some(mut img) => { timed!("Saving", img.save_png(out_png)); }
+        if args.perf {
+            let stats = resvg::perf::take_stats();
+            print_duration("Filters", stats.filters);
+            print_duration("Clipping", stats.clip_paths);
+            print_duration("Masking", stats.masks);
+        }
+
+        let mut img = match img {
+            Some(img) => img,
             None => { bail!("failed to allocate an image") }
+        };
+
+        timed!("Saving", img.save_png(out_png));
+
+        if let Some(ref reference) = args.compare {
+            compare::compare(&mut *img, reference, args.diff_threshold, args.diff_out.as_deref())?;
         }
     };
 
     Ok(())
 }
 
+#[cfg(feature = "watch")]
+fn watch(args: &args::Args, opt: &resvg::Options, backend: &dyn Render) -> Result<(), String> {
+    use notify::Watcher;
+
+    // Editors usually save files atomically, i.e. write a temporary file and rename it,
+    // which replaces the watched file. So we are watching the parent directory instead.
+    let in_svg = args.in_svg.canonicalize().map_err(|e| e.to_string())?;
+    let dir = in_svg.parent().ok_or("the input file has no parent directory")?;
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut watcher = notify::watcher(tx, std::time::Duration::from_millis(100))
+        .map_err(|e| e.to_string())?;
+    watcher.watch(dir, notify::RecursiveMode::NonRecursive)
+        .map_err(|e| e.to_string())?;
+
+    println!("Watching {:?} for changes. Press Ctrl+C to stop.", args.in_svg);
+
+    loop {
+        let changed = match rx.recv() {
+            Ok(notify::DebouncedEvent::Write(path)) |
+            Ok(notify::DebouncedEvent::Create(path)) |
+            Ok(notify::DebouncedEvent::Rename(_, path)) => path == in_svg,
+            Ok(_) => false,
+            Err(e) => bail!(e),
+        };
+
+        if changed {
+            println!("[{}] Re-rendering {:?}", timestamp(), args.in_svg);
+            // Keep watching even if the file is temporarily invalid.
+            if let Err(e) = run(args, opt, backend) {
+                eprintln!("Error: {}.", e);
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "watch"))]
+fn watch(_: &args::Args, _: &resvg::Options, _: &dyn Render) -> Result<(), String> {
+    bail!("rendersvg has been built without the watch support")
+}
+
+/// Returns the current UTC time as `HH:MM:SS`.
+#[cfg(feature = "watch")]
+fn timestamp() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    format!("{:02}:{:02}:{:02}", secs / 3600 % 24, secs / 60 % 60, secs % 60)
+}
+
+/// Prints all IDs with bounding boxes.
+///
+/// Uses a geometric bounding box, where the stroke is only approximated,
+/// unlike `--export-id`, which uses a visual bounding box that includes
+/// the whole stroke and filter regions.
 fn query_all(tree: &usvg::Tree) -> Result<(), String> {
     let mut count = 0;
     for node in tree.root().descendants() {
@@ -153,13 +236,17 @@ fn run_task<P, T>(perf: bool, title: &str, p: P) -> T
     if perf {
         let now = std::time::Instant::now();
         let res = p();
-        println!("{}: {:.2}ms", title, now.elapsed().as_micros() as f64 / 1000.0);
+        print_duration(title, now.elapsed());
         res
     } else {
         p()
     }
 }
 
+fn print_duration(title: &str, d: std::time::Duration) {
+    println!("{}: {:.2}ms", title, d.as_micros() as f64 / 1000.0);
+}
+
 fn dump_svg(tree: &usvg::Tree, path: &path::Path) -> Result<(), String> {
     let mut f = fs::File::create(path)
         .map_err(|_| format!("failed to create a file {:?}", path))?;
@@ -242,6 +242,9 @@ fn process(args: &Args) -> Result<(), String> {
         text_rendering: args.text_rendering,
         image_rendering: args.image_rendering,
         keep_named_groups: args.keep_named_groups,
+        image_href_resolver: None,
+        allow_external_resources: true,
+        fallback_paint: None,
     };
 
     let input_str = match in_svg {
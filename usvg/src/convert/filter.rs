@@ -291,7 +291,7 @@ fn convert_fe_image(
         }
     };
 
-    let href = super::image::get_href_data(fe.element_id(), href, state.opt.path.as_ref());
+    let href = super::image::get_href_data(fe.element_id(), href, state.opt);
     let (img_data, format) = match href {
         Some((data, format)) => (data, format),
         None => return create_dummy_primitive(),
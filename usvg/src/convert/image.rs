@@ -4,7 +4,7 @@
 
 use std::path;
 
-use crate::{svgtree, tree, tree::prelude::*, utils};
+use crate::{svgtree, tree, tree::prelude::*, utils, Options};
 use super::prelude::*;
 
 
@@ -36,7 +36,7 @@ pub fn convert(
         "The 'image' element lacks the 'xlink:href' attribute. Skipped."
     );
 
-    let (data, format) = try_opt!(get_href_data(node.element_id(), href, state.opt.path.as_ref()));
+    let (data, format) = try_opt!(get_href_data(node.element_id(), href, state.opt));
     parent.append_kind(tree::NodeKind::Image(tree::Image {
         id: node.element_id().to_string(),
         transform: Default::default(),
@@ -51,7 +51,7 @@ pub fn convert(
 pub fn get_href_data(
     element_id: &str,
     href: &str,
-    path: Option<&path::PathBuf>,
+    opt: &Options,
 ) -> Option<(tree::ImageData, tree::ImageFormat)> {
     if let Ok(url) = data_url::DataUrl::process(href) {
         let (data, _) = url.decode_to_vec().ok()?;
@@ -63,13 +63,32 @@ pub fn get_href_data(
                 // Try to guess from raw data.
                 get_image_data_format(&data).unwrap_or(tree::ImageFormat::SVG)
             }
-            _ => return None,
+            _ => get_image_data_format(&data)?,
         };
 
         Some((tree::ImageData::Raw(data), format))
+    } else if !opt.allow_external_resources {
+        warn!("External image '{}' is not allowed. Skipped.", href);
+        None
+    } else if let Some(ref resolver) = opt.image_href_resolver {
+        let data = match (resolver.0)(href) {
+            Some(data) => data,
+            None => {
+                warn!("Image '{}' has an invalid 'xlink:href' content.", element_id);
+                return None;
+            }
+        };
+
+        match get_image_data_format(&data) {
+            Some(format) => Some((tree::ImageData::Raw(data), format)),
+            None => {
+                warn!("'{}' is not a PNG, JPEG or SVG(Z) image.", href);
+                None
+            }
+        }
     } else {
-        let path = match path {
-            Some(path) => path.parent()?.join(href),
+        let path = match opt.path {
+            Some(ref path) => path.parent()?.join(href),
             None => path::PathBuf::from(href),
         };
 
@@ -88,30 +107,63 @@ pub fn get_href_data(
 }
 
 /// Checks that file has a PNG or a JPEG magic bytes.
-/// Or an SVG(Z) extension.
+/// Or an SVG(Z) extension or content.
 fn get_image_file_format(path: &path::Path) -> Option<tree::ImageFormat> {
     use std::io::Read;
 
-    let ext = utils::file_extension(path)?.to_lowercase();
-    if ext == "svg" || ext == "svgz" {
-        return Some(tree::ImageFormat::SVG);
+    if let Some(ext) = utils::file_extension(path) {
+        let ext = ext.to_lowercase();
+        if ext == "svg" || ext == "svgz" {
+            return Some(tree::ImageFormat::SVG);
+        }
     }
 
-    let mut file = std::fs::File::open(path).ok()?;
+    let file = std::fs::File::open(path).ok()?;
 
-    let mut d = [0; 8];
-    file.read_exact(&mut d).ok()?;
+    let mut d = Vec::with_capacity(SNIFF_LEN);
+    file.take(SNIFF_LEN as u64).read_to_end(&mut d).ok()?;
 
     get_image_data_format(&d)
 }
 
-/// Checks that file has a PNG or a JPEG magic bytes.
+/// An amount of bytes used to detect an image format.
+const SNIFF_LEN: usize = 512;
+
+/// Checks that data has a PNG or a JPEG magic bytes.
+/// Or looks like an SVG(Z).
 fn get_image_data_format(data: &[u8]) -> Option<tree::ImageFormat> {
     if data.starts_with(b"\x89PNG\r\n\x1a\n") {
         Some(tree::ImageFormat::PNG)
     } else if data.starts_with(&[0xff, 0xd8, 0xff]) {
         Some(tree::ImageFormat::JPEG)
+    } else if is_svg_data(data) {
+        Some(tree::ImageFormat::SVG)
     } else {
         None
     }
 }
+
+/// Checks that data is a GZip stream or an XML document with an `svg` element
+/// near the beginning.
+///
+/// This is just a heuristic. The actual document will be validated during parsing.
+fn is_svg_data(data: &[u8]) -> bool {
+    // SVGZ.
+    if data.starts_with(&[0x1f, 0x8b]) {
+        return true;
+    }
+
+    let data = &data[0..std::cmp::min(data.len(), SNIFF_LEN)];
+    // Skip UTF-8 BOM.
+    let data = if data.starts_with(b"\xEF\xBB\xBF") { &data[3..] } else { data };
+    let start = match data.iter().position(|c| !c.is_ascii_whitespace()) {
+        Some(idx) => &data[idx..],
+        None => return false,
+    };
+
+    if !start.starts_with(b"<") {
+        return false;
+    }
+
+    data.windows(4).any(|w| w == b"<svg")
+}
@@ -95,13 +95,7 @@ fn resolve(
         }
     );
 
-    let has_overflow = {
-        let overflow = marker_node.attribute(AId::Overflow);
-        // `overflow` is `hidden` by default.
-        overflow == None || overflow == Some("hidden") || overflow == Some("scroll")
-    };
-
-    let clip_path = if has_overflow {
+    let clip_path = if !use_node::is_overflow_visible(marker_node) {
         let clip_rect = if let Some(vbox) = view_box {
             vbox.rect
         } else {
@@ -398,6 +398,7 @@ fn convert_group(
             filter_fill,
             filter_stroke,
             enable_background,
+            knockout: false,
         }));
 
         GroupKind::Create(g)
@@ -490,16 +491,22 @@ fn ungroup_groups(
             curr_node = node.next_sibling();
 
             let mut ts = tree::Transform::default();
+            let mut opacity = tree::Opacity::default();
             let is_ok = if let tree::NodeKind::Group(ref g) = *node.borrow() {
                 ts = g.transform;
-
-                   g.opacity.is_default()
-                && g.clip_path.is_none()
-                && g.mask.is_none()
-                && g.filter.is_none()
-                && g.enable_background.is_none()
-                && !(opt.keep_named_groups && !g.id.is_empty())
-                && !is_id_used(&parent.tree(), &g.id)
+                opacity = g.opacity;
+
+                let is_plain =
+                       g.clip_path.is_none()
+                    && g.mask.is_none()
+                    && g.filter.is_none()
+                    && g.enable_background.is_none()
+                    && !(opt.keep_named_groups && !g.id.is_empty())
+                    && !is_id_used(&parent.tree(), &g.id);
+
+                // A group opacity can be merged into a single child group,
+                // because the group layer will contain only the child layer anyway.
+                is_plain && (g.opacity.is_default() || has_single_group_child(&node))
             } else {
                 false
             };
@@ -519,6 +526,7 @@ fn ungroup_groups(
                         }
                         tree::NodeKind::Group(ref mut g) => {
                             g.transform.prepend(&ts);
+                            g.opacity = g.opacity * opacity;
                         }
                         _ => {}
                     }
@@ -542,6 +550,16 @@ fn ungroup_groups(
     while ungroup(tree.root(), opt) {}
 }
 
+fn has_single_group_child(node: &tree::Node) -> bool {
+    match node.first_child() {
+        Some(child) => {
+               child.next_sibling().is_none()
+            && matches!(*child.borrow(), tree::NodeKind::Group(_))
+        }
+        None => false,
+    }
+}
+
 fn remove_unused_defs(
     tree: &mut tree::Tree,
 ) {
@@ -709,6 +727,9 @@ fn convert_path(
         visibility = tree::Visibility::Hidden;
     }
 
+    let order = style::resolve_paint_order(node);
+    let paint_order = style::to_paint_order(&order);
+
     let mut markers_group = None;
     if marker::is_valid(node) && visibility == tree::Visibility::Visible {
         let mut g = parent.append_kind(tree::NodeKind::Group(tree::Group::default()));
@@ -716,21 +737,84 @@ fn convert_path(
         markers_group = Some(g);
     }
 
-    parent.append_kind(tree::NodeKind::Path(tree::Path {
+    let path = tree::Path {
         id: node.element_id().to_string(),
         transform: Default::default(),
         visibility,
         fill,
         stroke,
         rendering_mode,
+        paint_order,
         data: path,
-    }));
+    };
 
-    // Insert markers group after `path`.
-    if let Some(mut g) = markers_group {
-        g.detach();
-        parent.append(g);
+    let mut markers_group = match markers_group {
+        Some(g) => g,
+        None => {
+            parent.append_kind(tree::NodeKind::Path(path));
+            return;
+        }
+    };
+
+    match order.iter().position(|k| *k == style::PaintOrderKind::Markers) {
+        Some(0) => {
+            // Markers group is already inserted before `path`.
+            parent.append_kind(tree::NodeKind::Path(path));
+        }
+        Some(1) => {
+            // Markers are painted between fill and stroke,
+            // so we have to split the path into two.
+            let (first, second) = split_by_paint(path);
+            if let Some(first) = first {
+                parent.append_kind(tree::NodeKind::Path(first));
+            }
+
+            markers_group.detach();
+            parent.append(markers_group);
+
+            if let Some(second) = second {
+                parent.append_kind(tree::NodeKind::Path(second));
+            }
+        }
+        _ => {
+            parent.append_kind(tree::NodeKind::Path(path));
+
+            // Insert markers group after `path`.
+            markers_group.detach();
+            parent.append(markers_group);
+        }
+    }
+}
+
+/// Splits a path into the one painted first and the one painted second.
+///
+/// A path without a paint is omitted.
+fn split_by_paint(path: tree::Path) -> (Option<tree::Path>, Option<tree::Path>) {
+    let mut first = path.clone();
+    let mut second = path;
+    match first.paint_order {
+        tree::PaintOrder::FillAndStroke => {
+            first.stroke = None;
+            second.fill = None;
+        }
+        tree::PaintOrder::StrokeAndFill => {
+            first.fill = None;
+            second.stroke = None;
+        }
     }
+
+    let has_paint = |p: &tree::Path| p.fill.is_some() || p.stroke.is_some();
+    let first = if has_paint(&first) { Some(first) } else { None };
+    let mut second = if has_paint(&second) { Some(second) } else { None };
+
+    // Only the first path should preserve the ID.
+    if first.is_some() {
+        if let Some(ref mut p) = second {
+            p.id.clear();
+        }
+    }
+
+    (first, second)
 }
 
 
@@ -74,6 +74,72 @@ pub fn resolve_stroke(
     Some(stroke)
 }
 
+/// A `paint-order` item.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PaintOrderKind {
+    Fill,
+    Stroke,
+    Markers,
+}
+
+/// Resolves the `paint-order` attribute into a full painting order.
+///
+/// Omitted items are painted afterwards in the default order.
+/// Invalid values fallback to the default order.
+pub fn resolve_paint_order(node: svgtree::Node) -> [PaintOrderKind; 3] {
+    use PaintOrderKind as K;
+
+    let def = [K::Fill, K::Stroke, K::Markers];
+
+    let value: &str = match node.find_attribute(AId::PaintOrder) {
+        Some(v) => v,
+        None => return def,
+    };
+
+    if value.trim() == "normal" {
+        return def;
+    }
+
+    let mut order = Vec::with_capacity(3);
+    for name in value.split_whitespace() {
+        let kind = match name {
+            "fill" => K::Fill,
+            "stroke" => K::Stroke,
+            "markers" => K::Markers,
+            _ => return def,
+        };
+
+        if order.contains(&kind) {
+            return def;
+        }
+
+        order.push(kind);
+    }
+
+    if order.is_empty() {
+        return def;
+    }
+
+    for kind in &def {
+        if !order.contains(kind) {
+            order.push(*kind);
+        }
+    }
+
+    [order[0], order[1], order[2]]
+}
+
+/// Returns a fill and stroke painting order.
+pub fn to_paint_order(order: &[PaintOrderKind; 3]) -> tree::PaintOrder {
+    let fill_idx = order.iter().position(|k| *k == PaintOrderKind::Fill);
+    let stroke_idx = order.iter().position(|k| *k == PaintOrderKind::Stroke);
+    if fill_idx < stroke_idx {
+        tree::PaintOrder::FillAndStroke
+    } else {
+        tree::PaintOrder::StrokeAndFill
+    }
+}
+
 fn convert_paint(
     node: svgtree::Node,
     aid: AId,
@@ -118,6 +184,9 @@ fn convert_paint(
                     warn!("'{}' cannot be used to {} a shape.", tag_name, aid);
                     None
                 }
+            } else if let (None, Some(color)) = (fallback, state.opt.fallback_paint) {
+                warn!("Paint server '{}' doesn't exist. Fallback color is used.", func_iri);
+                Some(tree::Paint::Color(color))
             } else {
                 from_fallback(node, *fallback)
             }
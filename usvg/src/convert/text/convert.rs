@@ -107,6 +107,7 @@ pub struct TextSpan {
     pub decoration: TextDecoration,
     pub baseline_shift: f64,
     pub visibility: tree::Visibility,
+    pub paint_order: tree::PaintOrder,
     pub letter_spacing: f64,
     pub word_spacing: f64,
 }
@@ -230,6 +231,7 @@ fn collect_text_chunks_impl(
             font_size,
             decoration: resolve_decoration(text_node, parent, state, tree),
             visibility: parent.find_attribute(AId::Visibility).unwrap_or_default(),
+            paint_order: style::to_paint_order(&style::resolve_paint_order(parent)),
             baseline_shift: resolve_baseline_shift(parent, state),
             letter_spacing: parent.resolve_length(AId::LetterSpacing, state, 0.0),
             word_spacing: parent.resolve_length(AId::WordSpacing, state, 0.0),
@@ -348,6 +350,15 @@ pub fn resolve_rendering_mode(
     }
 }
 
+/// Splits a `font-family` value into separate family names.
+pub fn split_font_families(value: &str) -> Vec<String> {
+    // TODO: to a proper parser
+    value.split(',')
+        .map(|family| family.replace('\'', "").replace('"', "").trim().to_string())
+        .filter(|family| !family.is_empty())
+        .collect()
+}
+
 fn resolve_font(
     node: svgtree::Node,
     state: &State,
@@ -363,13 +374,7 @@ fn resolve_font(
         state.opt.font_family.to_owned()
     };
 
-    let mut name_list = Vec::new();
-    for family in font_family.split(',') {
-        // TODO: to a proper parser
-        let family = family.replace('\'', "");
-        let family = family.trim();
-        name_list.push(family.to_string());
-    }
+    let mut name_list = split_font_families(&font_family);
 
     // Use the default font as fallback.
     name_list.push(state.opt.font_family.clone());
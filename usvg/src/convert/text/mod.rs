@@ -60,6 +60,8 @@ pub fn convert(
 ) {
     state.db.borrow_mut().populate();
 
+    collect_font_families(node, state.opt, &mut tree.font_families);
+
     let text_node = TextNode::new(node.clone());
     let mut new_paths = text_to_paths(text_node, state, parent, tree);
 
@@ -93,6 +95,27 @@ pub fn convert(
     }
 }
 
+/// Collects distinct `font-family` names referenced by the `text` element.
+fn collect_font_families(
+    text: svgtree::Node,
+    opt: &Options,
+    families: &mut Vec<String>,
+) {
+    for node in text.descendants() {
+        if !node.is_text() || node.text().trim().is_empty() {
+            continue;
+        }
+
+        let parent = try_opt_continue!(node.parent_element());
+        let value = parent.find_attribute(AId::FontFamily).unwrap_or(opt.font_family.as_str());
+        for family in split_font_families(value) {
+            if !families.iter().any(|f| *f == family) {
+                families.push(family);
+            }
+        }
+    }
+}
+
 fn text_to_paths(
     text_node: TextNode,
     state: &State,
@@ -247,6 +270,7 @@ fn convert_span(
         fill,
         stroke: span.stroke.take(),
         rendering_mode: tree::ShapeRendering::default(),
+        paint_order: span.paint_order,
         data: Rc::new(path_data),
     };
 
@@ -357,6 +381,7 @@ fn convert_decoration(
         visibility: span.visibility,
         fill: decoration.fill.take(),
         stroke: decoration.stroke.take(),
+        paint_order: span.paint_order,
         data: Rc::new(path),
         .. tree::Path::default()
     }
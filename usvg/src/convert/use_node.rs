@@ -168,7 +168,7 @@ fn get_clip_rect(
     state: &State,
 ) -> Option<Rect> {
     // No need to clip elements with overflow:visible.
-    if matches!(symbol_node.attribute(AId::Overflow), Some("visible") | Some("auto")) {
+    if is_overflow_visible(symbol_node) {
         return None;
     }
 
@@ -184,15 +184,25 @@ fn get_clip_rect(
         return None;
     }
 
-    // TODO: add a test case
-    // Clip rect is not needed when it has the same size as a whole image.
-    if w.fuzzy_eq(&state.size.width()) && h.fuzzy_eq(&state.size.height()) {
+    // Clip rect is not needed when it matches a whole image.
+    // A viewport with an offset still has to be clipped from the top-left side.
+    if x.is_fuzzy_zero() && y.is_fuzzy_zero() &&
+       w.fuzzy_eq(&state.size.width()) && h.fuzzy_eq(&state.size.height())
+    {
         return None;
     }
 
     Rect::new(x, y, w, h)
 }
 
+/// Checks that a viewport element content should not be clipped.
+///
+/// Applies to `marker`, `symbol` and nested `svg`, which have `overflow=hidden` by default.
+/// `scroll` is treated as `hidden` and `auto` as `visible`.
+pub fn is_overflow_visible(node: svgtree::Node) -> bool {
+    matches!(node.attribute(AId::Overflow), Some("visible") | Some("auto"))
+}
+
 /// Creates a free id for `clipPath`.
 pub fn gen_clip_path_id(
     node: svgtree::Node,
@@ -41,6 +41,9 @@ and an XML library with some small amount of code.
 - No DTD
 - No CSS (partial support)
 - No `script` (simply ignoring it)
+- No text. Text is converted to paths during parsing.
+  System fonts are loaded only when a document actually contains text,
+  so parsing icon-like documents doesn't require fonts
 
 Full spec can be found [here](https://github.com/RazrFalcon/resvg/blob/master/docs/usvg_spec.adoc).
 
@@ -2,15 +2,40 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::fmt;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use crate::{
+    Color,
     ImageRendering,
     ShapeRendering,
     TextRendering,
 };
 
 
+/// A custom image `xlink:href` resolver.
+///
+/// Receives an `xlink:href` value as is and returns the referenced file content.
+#[derive(Clone)]
+pub struct ImageHrefResolver(pub Arc<dyn Fn(&str) -> Option<Vec<u8>> + Send + Sync>);
+
+impl ImageHrefResolver {
+    /// Creates a new resolver from a closure.
+    pub fn new<F>(f: F) -> Self
+        where F: Fn(&str) -> Option<Vec<u8>> + Send + Sync + 'static
+    {
+        ImageHrefResolver(Arc::new(f))
+    }
+}
+
+impl fmt::Debug for ImageHrefResolver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ImageHrefResolver(..)")
+    }
+}
+
+
 /// Processing options.
 #[derive(Clone, Debug)]
 pub struct Options {
@@ -56,6 +81,41 @@ pub struct Options {
     /// If set to `true`, all non-empty groups with `id` attribute will not
     /// be removed.
     pub keep_named_groups: bool,
+
+    /// A custom resolver for external images.
+    ///
+    /// By default, `image` and `feImage` elements can reference any file
+    /// accessible by the current process, relative to `path` or absolute.
+    /// Which is unsafe when processing untrusted SVG files.
+    ///
+    /// When set, all non-`data:` `xlink:href` values are passed to this resolver
+    /// instead and the filesystem will not be accessed at all.
+    /// This allows loading images from a virtual filesystem or denying them altogether.
+    /// An image will be skipped when `None` is returned.
+    ///
+    /// `data:` URLs are always decoded in place.
+    ///
+    /// Default: None
+    pub image_href_resolver: Option<ImageHrefResolver>,
+
+    /// Allows loading external resources.
+    ///
+    /// When disabled, only inline `data:` URLs are allowed
+    /// and all other `xlink:href` values will be ignored with a warning,
+    /// even when `image_href_resolver` is set.
+    /// Should be disabled when processing untrusted SVG files.
+    ///
+    /// Default: true
+    pub allow_external_resources: bool,
+
+    /// A color that will be used for `fill` and `stroke` referencing a missing paint server.
+    ///
+    /// By default, such shapes are not painted, according to the SVG spec,
+    /// which makes broken references hard to notice. A bright color, like magenta,
+    /// can be used for debugging. A fallback set in SVG itself has a higher priority.
+    ///
+    /// Default: None
+    pub fallback_paint: Option<Color>,
 }
 
 impl Default for Options {
@@ -71,6 +131,9 @@ impl Default for Options {
             text_rendering: TextRendering::default(),
             image_rendering: ImageRendering::default(),
             keep_named_groups: false,
+            image_href_resolver: None,
+            allow_external_resources: true,
+            fallback_paint: None,
         }
     }
 }
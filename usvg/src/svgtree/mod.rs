@@ -764,6 +764,7 @@ impl AId {
             | AId::Mask
             | AId::Opacity
             | AId::Overflow
+            | AId::PaintOrder
             | AId::ShapeRendering
             | AId::StopColor
             | AId::StopOpacity
@@ -820,6 +821,7 @@ impl AId {
             | AId::Mask
             | AId::Opacity
             | AId::Overflow
+            | AId::PaintOrder
             | AId::ShapeRendering
             | AId::StopColor
             | AId::StopOpacity
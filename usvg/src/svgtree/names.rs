@@ -229,6 +229,7 @@ pub enum AId {
     Order,
     Orient,
     Overflow,
+    PaintOrder,
     PatternContentUnits,
     PatternTransform,
     PatternUnits,
@@ -301,178 +302,179 @@ pub enum AId {
 static ATTRIBUTES: Map<AId> = Map {
     key: 3213172566270843353,
     disps: &[
-        (0, 32),
-        (0, 1),
-        (0, 16),
-        (0, 31),
-        (9, 125),
-        (1, 4),
-        (5, 102),
-        (0, 44),
-        (11, 69),
-        (5, 77),
+        (0, 13),
+        (1, 7),
+        (4, 131),
+        (0, 7),
+        (16, 105),
         (0, 20),
-        (0, 8),
-        (0, 3),
-        (0, 8),
-        (2, 43),
-        (1, 0),
+        (3, 17),
+        (0, 5),
+        (95, 79),
+        (5, 45),
+        (0, 14),
+        (0, 24),
         (0, 0),
-        (25, 133),
-        (12, 99),
-        (0, 3),
+        (0, 6),
+        (0, 77),
+        (1, 3),
+        (1, 8),
+        (23, 127),
+        (129, 108),
+        (3, 18),
+        (0, 56),
+        (0, 4),
+        (1, 3),
+        (2, 95),
+        (6, 102),
+        (2, 72),
         (0, 68),
-        (0, 33),
-        (1, 54),
-        (0, 64),
-        (2, 119),
-        (0, 18),
-        (0, 7),
-        (1, 19),
-        (9, 46),
+        (0, 4),
+        (7, 45),
     ],
     entries: &[
-        ("pointsAtZ", AId::PointsAtZ),
-        ("stop-color", AId::StopColor),
-        ("xChannelSelector", AId::XChannelSelector),
-        ("systemLanguage", AId::SystemLanguage),
-        ("cy", AId::Cy),
-        ("dy", AId::Dy),
-        ("preserveAlpha", AId::PreserveAlpha),
-        ("preserveAspectRatio", AId::PreserveAspectRatio),
-        ("lighting-color", AId::LightingColor),
-        ("stroke-dashoffset", AId::StrokeDashoffset),
-        ("word-spacing", AId::WordSpacing),
-        ("font-style", AId::FontStyle),
-        ("edgeMode", AId::EdgeMode),
-        ("pointsAtY", AId::PointsAtY),
-        ("id", AId::Id),
-        ("type", AId::Type),
-        ("targetX", AId::TargetX),
-        ("fill", AId::Fill),
-        ("k3", AId::K3),
+        ("diffuseConstant", AId::DiffuseConstant),
+        ("color", AId::Color),
+        ("text-decoration", AId::TextDecoration),
         ("kernelUnitLength", AId::KernelUnitLength),
-        ("viewBox", AId::ViewBox),
         ("baseFrequency", AId::BaseFrequency),
-        ("stroke", AId::Stroke),
+        ("paint-order", AId::PaintOrder),
+        ("fx", AId::Fx),
+        ("targetY", AId::TargetY),
+        ("elevation", AId::Elevation),
+        ("in2", AId::In2),
+        ("filter", AId::Filter),
+        ("y", AId::Y),
+        ("kernelMatrix", AId::KernelMatrix),
+        ("stroke-miterlimit", AId::StrokeMiterlimit),
+        ("color-interpolation-filters", AId::ColorInterpolationFilters),
+        ("font-size", AId::FontSize),
+        ("r", AId::R),
+        ("cx", AId::Cx),
+        ("writing-mode", AId::WritingMode),
+        ("maskUnits", AId::MaskUnits),
+        ("primitiveUnits", AId::PrimitiveUnits),
+        ("surfaceScale", AId::SurfaceScale),
+        ("stitchTiles", AId::StitchTiles),
+        ("refY", AId::RefY),
+        ("orient", AId::Orient),
+        ("clip-rule", AId::ClipRule),
+        ("marker-mid", AId::MarkerMid),
+        ("x1", AId::X1),
+        ("ry", AId::Ry),
+        ("in", AId::In),
+        ("x2", AId::X2),
+        ("direction", AId::Direction),
+        ("marker-end", AId::MarkerEnd),
+        ("rotate", AId::Rotate),
         ("divisor", AId::Divisor),
-        ("slope", AId::Slope),
-        ("markerUnits", AId::MarkerUnits),
-        ("d", AId::D),
-        ("clipPathUnits", AId::ClipPathUnits),
-        ("stop-opacity", AId::StopOpacity),
-        ("transform", AId::Transform),
+        ("viewBox", AId::ViewBox),
+        ("xChannelSelector", AId::XChannelSelector),
+        ("style", AId::Style),
+        ("pointsAtZ", AId::PointsAtZ),
+        ("values", AId::Values),
+        ("stroke-linejoin", AId::StrokeLinejoin),
         ("stroke-dasharray", AId::StrokeDasharray),
+        ("k1", AId::K1),
+        ("maskContentUnits", AId::MaskContentUnits),
+        ("id", AId::Id),
+        ("startOffset", AId::StartOffset),
+        ("radius", AId::Radius),
+        ("targetX", AId::TargetX),
+        ("patternTransform", AId::PatternTransform),
+        ("result", AId::Result),
+        ("display", AId::Display),
+        ("dy", AId::Dy),
+        ("slope", AId::Slope),
+        ("systemLanguage", AId::SystemLanguage),
         ("dx", AId::Dx),
-        ("specularExponent", AId::SpecularExponent),
-        ("z", AId::Z),
-        ("direction", AId::Direction),
+        ("fill", AId::Fill),
+        ("font-weight", AId::FontWeight),
+        ("cy", AId::Cy),
+        ("offset", AId::Offset),
+        ("bias", AId::Bias),
         ("letter-spacing", AId::LetterSpacing),
-        ("stroke-miterlimit", AId::StrokeMiterlimit),
-        ("overflow", AId::Overflow),
-        ("order", AId::Order),
+        ("numOctaves", AId::NumOctaves),
+        ("space", AId::Space),
+        ("patternContentUnits", AId::PatternContentUnits),
         ("intercept", AId::Intercept),
-        ("operator", AId::Operator),
-        ("font-variant", AId::FontVariant),
-        ("filterUnits", AId::FilterUnits),
-        ("values", AId::Values),
-        ("filter", AId::Filter),
-        ("rotate", AId::Rotate),
-        ("href", AId::Href),
-        ("requiredExtensions", AId::RequiredExtensions),
-        ("font-stretch", AId::FontStretch),
-        ("clip-path", AId::ClipPath),
-        ("x1", AId::X1),
-        ("pointsAtX", AId::PointsAtX),
+        ("text-rendering", AId::TextRendering),
+        ("k2", AId::K2),
+        ("yChannelSelector", AId::YChannelSelector),
         ("k4", AId::K4),
-        ("font-weight", AId::FontWeight),
-        ("class", AId::Class),
-        ("patternTransform", AId::PatternTransform),
-        ("markerHeight", AId::MarkerHeight),
-        ("writing-mode", AId::WritingMode),
-        ("clip-rule", AId::ClipRule),
-        ("y1", AId::Y1),
-        ("patternContentUnits", AId::PatternContentUnits),
-        ("primitiveUnits", AId::PrimitiveUnits),
-        ("x2", AId::X2),
-        ("y", AId::Y),
-        ("marker-mid", AId::MarkerMid),
-        ("offset", AId::Offset),
-        ("stroke-width", AId::StrokeWidth),
-        ("markerWidth", AId::MarkerWidth),
-        ("fill-opacity", AId::FillOpacity),
+        ("tableValues", AId::TableValues),
+        ("z", AId::Z),
         ("refX", AId::RefX),
-        ("maskContentUnits", AId::MaskContentUnits),
-        ("shape-rendering", AId::ShapeRendering),
-        ("amplitude", AId::Amplitude),
-        ("stitchTiles", AId::StitchTiles),
-        ("maskUnits", AId::MaskUnits),
-        ("fx", AId::Fx),
-        ("color", AId::Color),
-        ("numOctaves", AId::NumOctaves),
-        ("stdDeviation", AId::StdDeviation),
-        ("y2", AId::Y2),
         ("mask", AId::Mask),
+        ("order", AId::Order),
+        ("overflow", AId::Overflow),
+        ("clipPathUnits", AId::ClipPathUnits),
+        ("text-anchor", AId::TextAnchor),
+        ("stop-opacity", AId::StopOpacity),
         ("spreadMethod", AId::SpreadMethod),
-        ("display", AId::Display),
-        ("opacity", AId::Opacity),
-        ("font-size", AId::FontSize),
-        ("stroke-linejoin", AId::StrokeLinejoin),
+        ("font-style", AId::FontStyle),
+        ("stroke", AId::Stroke),
+        ("class", AId::Class),
         ("image-rendering", AId::ImageRendering),
-        ("azimuth", AId::Azimuth),
-        ("in", AId::In),
-        ("ry", AId::Ry),
-        ("bias", AId::Bias),
-        ("radius", AId::Radius),
-        ("enable-background", AId::EnableBackground),
-        ("gradientUnits", AId::GradientUnits),
-        ("specularConstant", AId::SpecularConstant),
-        ("limitingConeAngle", AId::LimitingConeAngle),
-        ("flood-color", AId::FloodColor),
-        ("scale", AId::Scale),
+        ("type", AId::Type),
+        ("shape-rendering", AId::ShapeRendering),
+        ("exponent", AId::Exponent),
+        ("fill-opacity", AId::FillOpacity),
         ("x", AId::X),
-        ("diffuseConstant", AId::DiffuseConstant),
-        ("refY", AId::RefY),
-        ("yChannelSelector", AId::YChannelSelector),
-        ("style", AId::Style),
-        ("gradientTransform", AId::GradientTransform),
-        ("fill-rule", AId::FillRule),
-        ("height", AId::Height),
-        ("kernelMatrix", AId::KernelMatrix),
-        ("text-anchor", AId::TextAnchor),
+        ("clip-path", AId::ClipPath),
+        ("d", AId::D),
         ("stroke-linecap", AId::StrokeLinecap),
-        ("text-decoration", AId::TextDecoration),
-        ("rx", AId::Rx),
-        ("requiredFeatures", AId::RequiredFeatures),
-        ("patternUnits", AId::PatternUnits),
+        ("operator", AId::Operator),
+        ("opacity", AId::Opacity),
+        ("fy", AId::Fy),
+        ("font-variant", AId::FontVariant),
+        ("edgeMode", AId::EdgeMode),
+        ("font-stretch", AId::FontStretch),
+        ("y2", AId::Y2),
         ("mode", AId::Mode),
-        ("marker-start", AId::MarkerStart),
-        ("visibility", AId::Visibility),
-        ("result", AId::Result),
+        ("specularExponent", AId::SpecularExponent),
+        ("baseline-shift", AId::BaselineShift),
+        ("k3", AId::K3),
+        ("patternUnits", AId::PatternUnits),
+        ("azimuth", AId::Azimuth),
+        ("font-family", AId::FontFamily),
+        ("limitingConeAngle", AId::LimitingConeAngle),
+        ("points", AId::Points),
+        ("fill-rule", AId::FillRule),
+        ("requiredExtensions", AId::RequiredExtensions),
+        ("markerWidth", AId::MarkerWidth),
+        ("amplitude", AId::Amplitude),
         ("seed", AId::Seed),
-        ("in2", AId::In2),
-        ("cx", AId::Cx),
-        ("k2", AId::K2),
+        ("href", AId::Href),
         ("flood-opacity", AId::FloodOpacity),
-        ("k1", AId::K1),
-        ("text-rendering", AId::TextRendering),
-        ("r", AId::R),
-        ("marker-end", AId::MarkerEnd),
-        ("surfaceScale", AId::SurfaceScale),
-        ("tableValues", AId::TableValues),
-        ("orient", AId::Orient),
-        ("color-interpolation-filters", AId::ColorInterpolationFilters),
-        ("points", AId::Points),
-        ("baseline-shift", AId::BaselineShift),
-        ("elevation", AId::Elevation),
+        ("rx", AId::Rx),
+        ("transform", AId::Transform),
+        ("pointsAtX", AId::PointsAtX),
+        ("stop-color", AId::StopColor),
+        ("marker-start", AId::MarkerStart),
+        ("preserveAspectRatio", AId::PreserveAspectRatio),
+        ("pointsAtY", AId::PointsAtY),
+        ("height", AId::Height),
+        ("filterUnits", AId::FilterUnits),
+        ("y1", AId::Y1),
+        ("lighting-color", AId::LightingColor),
         ("width", AId::Width),
-        ("font-family", AId::FontFamily),
-        ("space", AId::Space),
-        ("startOffset", AId::StartOffset),
-        ("fy", AId::Fy),
+        ("gradientUnits", AId::GradientUnits),
+        ("stroke-width", AId::StrokeWidth),
+        ("gradientTransform", AId::GradientTransform),
+        ("stdDeviation", AId::StdDeviation),
+        ("preserveAlpha", AId::PreserveAlpha),
         ("stroke-opacity", AId::StrokeOpacity),
-        ("targetY", AId::TargetY),
-        ("exponent", AId::Exponent),
+        ("flood-color", AId::FloodColor),
+        ("requiredFeatures", AId::RequiredFeatures),
+        ("scale", AId::Scale),
+        ("enable-background", AId::EnableBackground),
+        ("specularConstant", AId::SpecularConstant),
+        ("word-spacing", AId::WordSpacing),
+        ("markerUnits", AId::MarkerUnits),
+        ("stroke-dashoffset", AId::StrokeDashoffset),
+        ("markerHeight", AId::MarkerHeight),
+        ("visibility", AId::Visibility),
     ],
 };
 
@@ -841,6 +841,7 @@ fn resolve_inherit(
         | AId::FontVariant
         | AId::FontWeight
         | AId::LetterSpacing
+        | AId::PaintOrder
         | AId::WordSpacing => "normal",
 
           AId::Fill
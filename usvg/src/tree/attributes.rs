@@ -492,6 +492,21 @@ impl_enum_from_str!(ShapeRendering,
 impl_from_str!(ShapeRendering);
 
 
+/// A fill and stroke painting order.
+///
+/// `paint-order` attribute in the SVG.
+///
+/// Markers order is resolved by `usvg` itself.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub enum PaintOrder {
+    FillAndStroke,
+    StrokeAndFill,
+}
+
+impl_enum_default!(PaintOrder, FillAndStroke);
+
+
 /// A text rendering method.
 ///
 /// `text-rendering` attribute in the SVG.
@@ -528,7 +543,13 @@ impl_enum_default!(ImageRendering, OptimizeQuality);
 
 impl_enum_from_str!(ImageRendering,
     "optimizeQuality"   => ImageRendering::OptimizeQuality,
-    "optimizeSpeed"     => ImageRendering::OptimizeSpeed
+    "optimizeSpeed"     => ImageRendering::OptimizeSpeed,
+    // CSS values. `pixelated` and `crisp-edges` both imply a nearest-neighbor scaling.
+    "auto"              => ImageRendering::OptimizeQuality,
+    "smooth"            => ImageRendering::OptimizeQuality,
+    "high-quality"      => ImageRendering::OptimizeQuality,
+    "pixelated"         => ImageRendering::OptimizeSpeed,
+    "crisp-edges"       => ImageRendering::OptimizeSpeed
 );
 
 impl_from_str!(ImageRendering);
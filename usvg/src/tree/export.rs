@@ -756,6 +756,10 @@ fn write_path(
         ShapeRendering::GeometricPrecision => {}
     }
 
+    if path.paint_order == PaintOrder::StrokeAndFill {
+        xml.write_svg_attribute(AId::PaintOrder, "stroke");
+    }
+
     if let Some(ref id) = clip_path {
         xml.write_func_iri(AId::ClipPath, id);
     }
@@ -35,6 +35,7 @@ pub type Node = rctree::Node<NodeKind>;
 #[derive(Clone)]
 pub struct Tree {
     root: Node,
+    pub(crate) font_families: Vec<String>,
 }
 
 impl Tree {
@@ -51,6 +52,44 @@ impl Tree {
         }
     }
 
+    /// Parses `Tree` from a reader.
+    ///
+    /// Can read an SVG string or a gzip compressed data.
+    ///
+    /// Unlike `from_data`, the input doesn't have to be loaded into memory first,
+    /// so a compressed input is decompressed on the fly and a plain one is read
+    /// directly into a string. The decompressed SVG text still has to be kept in memory
+    /// during parsing, so the peak memory usage is roughly the SVG text size plus the tree size.
+    ///
+    /// Read errors are reported as `Error::FileOpenFailed`.
+    pub fn from_reader<R: std::io::Read>(mut reader: R, opt: &Options) -> Result<Self, Error> {
+        use std::io::Read;
+
+        // Read the gzip magic.
+        let mut magic = [0; 2];
+        let mut len = 0;
+        while len < magic.len() {
+            match reader.read(&mut magic[len..]) {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(_) => return Err(Error::FileOpenFailed),
+            }
+        }
+
+        let mut reader = (&magic[..len]).chain(reader);
+        let mut data = Vec::new();
+        if magic[..len] == [0x1f, 0x8b] {
+            let mut decoder = flate2::read::GzDecoder::new(reader);
+            decoder.read_to_end(&mut data).map_err(|_| Error::MalformedGZip)?;
+        } else {
+            reader.read_to_end(&mut data).map_err(|_| Error::FileOpenFailed)?;
+        }
+
+        let text = String::from_utf8(data).map_err(|_| Error::NotAnUtf8Str)?;
+        Self::from_str(&text, opt)
+    }
+
     /// Parses `Tree` from the SVG string.
     pub fn from_str(text: &str, opt: &Options) -> Result<Self, Error> {
         let doc = svgtree::Document::parse(text).map_err(Error::ParsingFailed)?;
@@ -81,9 +120,25 @@ impl Tree {
 
         Tree {
             root: root_node,
+            font_families: Vec::new(),
         }
     }
 
+    /// Returns font families referenced by the rendered text.
+    ///
+    /// Contains distinct family names in the order they appear in the document.
+    /// Names are stored as authored, so they may not match the faces used for rendering.
+    /// Text without `font-family` references `Options::font_family`.
+    /// Text that is not rendered, like an unused one inside `defs` or with `display:none`,
+    /// is ignored.
+    ///
+    /// Always empty when the `text` feature is disabled
+    /// and for a tree returned by `NodeExt::tree`.
+    #[inline]
+    pub fn font_families(&self) -> &[String] {
+        &self.font_families
+    }
+
     /// Returns the `Svg` node.
     #[inline]
     pub fn root(&self) -> Node {
@@ -244,7 +299,7 @@ impl NodeExt for Node {
 
     #[inline]
     fn tree(&self) -> Tree {
-        Tree { root: self.root() }
+        Tree { root: self.root(), font_families: Vec::new() }
     }
 
     #[inline]
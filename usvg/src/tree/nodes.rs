@@ -118,6 +118,11 @@ pub struct Path {
     /// `shape-rendering` in SVG.
     pub rendering_mode: ShapeRendering,
 
+    /// Fill and stroke painting order.
+    ///
+    /// `paint-order` in SVG.
+    pub paint_order: PaintOrder,
+
     /// Segments list.
     ///
     /// All segments are in absolute coordinates.
@@ -133,6 +138,7 @@ impl Default for Path {
             fill: None,
             stroke: None,
             rendering_mode: ShapeRendering::default(),
+            paint_order: PaintOrder::default(),
             data: Rc::new(PathData::default()),
         }
     }
@@ -223,6 +229,16 @@ pub struct Group {
     ///
     /// `None` indicates an `accumulate` value.
     pub enable_background: Option<EnableBackground>,
+
+    /// Indicates that this is a knockout group.
+    ///
+    /// Each child of a knockout group is composited against the group's
+    /// initial backdrop and not against its previous siblings.
+    ///
+    /// Neither SVG nor CSS has a property for it, so it's never set by the parser
+    /// and can be enabled only via the API.
+    /// Currently supported only by the Skia backend.
+    pub knockout: bool,
 }
 
 impl Default for Group {
@@ -237,6 +253,7 @@ impl Default for Group {
             filter_fill: None,
             filter_stroke: None,
             enable_background: None,
+            knockout: false,
         }
     }
 }
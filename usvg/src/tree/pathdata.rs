@@ -185,6 +185,23 @@ impl PathData {
         calc_bbox_with_transform(self, ts, stroke)
     }
 
+    /// Calculates path's bounding box with a specified transform, including the stroke.
+    ///
+    /// Unlike `bbox_with_transform`, the stroke width is transformed as well,
+    /// and square caps and miter joins are taken into account.
+    /// It's still an approximation, but a conservative one,
+    /// so it can be used to allocate an image for the whole path.
+    ///
+    /// This operation is expensive.
+    #[inline]
+    pub fn stroke_bbox_with_transform(
+        &self,
+        ts: Transform,
+        stroke: &super::Stroke,
+    ) -> Option<Rect> {
+        calc_stroke_bbox_with_transform(self, ts, stroke)
+    }
+
     /// Checks that path has a bounding box.
     ///
     /// This operation is expensive.
@@ -391,6 +408,70 @@ fn calc_bbox_with_transform(
     ts: Transform,
     stroke: Option<&super::Stroke>,
 ) -> Option<Rect> {
+    let (mut minx, mut miny, mut maxx, mut maxy) = calc_extents_with_transform(segments, ts);
+
+    // TODO: find a better way
+    // It's an approximation, but it's better than nothing.
+    if let Some(ref stroke) = stroke {
+        let w = stroke.width.value() / 2.0;
+        minx -= w;
+        miny -= w;
+        maxx += w;
+        maxy += w;
+    }
+
+    let width = maxx - minx;
+    let height = maxy - miny;
+
+    Rect::new(minx, miny, width, height)
+}
+
+fn calc_stroke_bbox_with_transform(
+    segments: &[PathSegment],
+    ts: Transform,
+    stroke: &super::Stroke,
+) -> Option<Rect> {
+    let (minx, miny, maxx, maxy) = calc_extents_with_transform(segments, ts);
+
+    // The maximum distance between the stroke outline and the path in stroke half-widths.
+    let mut k = 1.0;
+    if stroke.linecap == super::LineCap::Square {
+        k = std::f64::consts::SQRT_2;
+    }
+
+    if stroke.linejoin == super::LineJoin::Miter && has_joins(segments) {
+        k = f64::max(k, stroke.miterlimit.value());
+    }
+
+    let (sx, sy) = ts.get_scale();
+    let w = stroke.width.value() / 2.0 * k * f64::max(sx, sy);
+
+    Rect::new(minx - w, miny - w, maxx - minx + w * 2.0, maxy - miny + w * 2.0)
+}
+
+/// Checks that path has at least one segment join.
+fn has_joins(segments: &[PathSegment]) -> bool {
+    let mut count = 0;
+    for seg in segments {
+        match seg {
+            PathSegment::MoveTo { .. } => count = 0,
+            PathSegment::LineTo { .. } | PathSegment::CurveTo { .. } => count += 1,
+            PathSegment::ClosePath => return true,
+        }
+
+        if count > 1 {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Returns transformed path's `(minx, miny, maxx, maxy)`.
+fn calc_extents_with_transform(
+    segments: &[PathSegment],
+    ts: Transform,
+) -> (f64, f64, f64, f64) {
     debug_assert!(!segments.is_empty());
 
     let mut prev_x = 0.0;
@@ -435,20 +516,7 @@ fn calc_bbox_with_transform(
         }
     }
 
-    // TODO: find a better way
-    // It's an approximation, but it's better than nothing.
-    if let Some(ref stroke) = stroke {
-        let w = stroke.width.value() / 2.0;
-        minx -= w;
-        miny -= w;
-        maxx += w;
-        maxy += w;
-    }
-
-    let width = maxx - minx;
-    let height = maxy - miny;
-
-    Rect::new(minx, miny, width, height)
+    (minx, miny, maxx, maxy)
 }
 
 fn has_bbox(segments: &[PathSegment]) -> bool {
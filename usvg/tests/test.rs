@@ -70,6 +70,8 @@ test!(group_with_an_invalid_child);
 test!(nested_group_with_an_invalid_child);
 test!(simple_switch);
 test!(switch_with_opacity);
+test!(nested_opacity_groups);
+test!(paint_order_stroke);
 test!(fe_image_duplicates);
 test!(fe_image_with_invalid_link);
 test!(fe_diffuse_lighting_without_light_source);
@@ -130,3 +132,263 @@ test_size_err!(size_detection_err_1,
 
 test_size_err!(size_detection_err_2,
     "<svg width='0' height='0' viewBox='0 0 10 20' xmlns='http://www.w3.org/2000/svg'>");
+
+fn has_image(tree: &usvg::Tree) -> bool {
+    tree.root().descendants().any(|n| {
+        if let usvg::NodeKind::Image(_) = *n.borrow() { true } else { false }
+    })
+}
+
+#[test]
+fn image_href_resolver_deny() {
+    let opt = usvg::Options {
+        image_href_resolver: Some(usvg::ImageHrefResolver::new(|_| None)),
+        .. usvg::Options::default()
+    };
+
+    let tree = usvg::Tree::from_str(
+        "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg' \
+              xmlns:xlink='http://www.w3.org/1999/xlink'>\
+             <image width='10' height='10' xlink:href='/etc/passwd'/>\
+         </svg>",
+        &opt,
+    ).unwrap();
+
+    assert!(!has_image(&tree));
+}
+
+#[test]
+fn image_href_resolver_virtual() {
+    let opt = usvg::Options {
+        image_href_resolver: Some(usvg::ImageHrefResolver::new(|href| {
+            if href == "image.png" {
+                Some(b"\x89PNG\r\n\x1a\n".to_vec())
+            } else {
+                None
+            }
+        })),
+        .. usvg::Options::default()
+    };
+
+    let tree = usvg::Tree::from_str(
+        "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg' \
+              xmlns:xlink='http://www.w3.org/1999/xlink'>\
+             <image width='10' height='10' xlink:href='image.png'/>\
+         </svg>",
+        &opt,
+    ).unwrap();
+
+    assert!(has_image(&tree));
+}
+
+#[test]
+fn disallow_external_resources() {
+    let svg = "\
+        <svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg' \
+             xmlns:xlink='http://www.w3.org/1999/xlink'>\
+            <image width='10' height='10' xlink:href='../../svg-tests/images/image-63x61.png'/>\
+        </svg>";
+
+    let opt = usvg::Options {
+        path: Some("tests/test.svg".into()),
+        .. usvg::Options::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &opt).unwrap();
+    assert!(has_image(&tree));
+
+    let opt = usvg::Options {
+        allow_external_resources: false,
+        .. opt
+    };
+    let tree = usvg::Tree::from_str(svg, &opt).unwrap();
+    assert!(!has_image(&tree));
+}
+
+#[test]
+fn bbox_with_hidden_elements() {
+    use usvg::{FuzzyEq, NodeExt};
+
+    let tree = usvg::Tree::from_str(
+        "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>\
+             <rect id='rect1' x='10' y='10' width='20' height='20'/>\
+             <rect id='rect2' x='50' y='50' width='20' height='20' visibility='hidden'/>\
+             <rect id='rect3' x='100' y='100' width='50' height='50' display='none'/>\
+         </svg>",
+        &usvg::Options::default(),
+    ).unwrap();
+
+    // `visibility:hidden` elements still have a bbox, while `display:none` ones are removed.
+    assert!(tree.node_by_id("rect2").is_some());
+    assert!(tree.node_by_id("rect3").is_none());
+
+    let bbox = tree.root().calculate_bbox().unwrap();
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 10.0, 60.0, 60.0).unwrap()));
+}
+
+#[test]
+fn font_families() {
+    let opt = usvg::Options {
+        font_family: "Default Sans".to_string(),
+        ..usvg::Options::default()
+    };
+
+    let tree = usvg::Tree::from_str(
+        "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>\
+             <text font-family=\"'Noto Sans', serif\">Text\
+                 <tspan font-family='Missing Font'>Text</tspan>\
+             </text>\
+             <text>Text</text>\
+             <g font-family='serif'><text>Text</text></g>\
+             <defs><text font-family='Unused'>Text</text></defs>\
+             <text font-family='Not Rendered' display='none'>Text</text>\
+         </svg>",
+        &opt,
+    ).unwrap();
+
+    assert_eq!(tree.font_families(), ["Noto Sans", "serif", "Missing Font", "Default Sans"]);
+}
+
+#[test]
+fn switch_languages() {
+    let svg = "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>\
+                   <switch>\
+                       <rect id='rect-fr' systemLanguage='fr' width='20' height='20'/>\
+                       <rect id='rect-en' systemLanguage='en-US, en-GB' width='20' height='20'/>\
+                       <rect id='rect-default' width='20' height='20'/>\
+                   </switch>\
+               </svg>";
+
+    let check = |languages: &[&str], id: &str| {
+        let opt = usvg::Options {
+            languages: languages.iter().map(|s| s.to_string()).collect(),
+            ..usvg::Options::default()
+        };
+        let tree = usvg::Tree::from_str(svg, &opt).unwrap();
+        for other in &["rect-fr", "rect-en", "rect-default"] {
+            assert_eq!(tree.node_by_id(other).is_some(), *other == id);
+        }
+    };
+
+    check(&["fr", "en"], "rect-fr");
+    check(&["en"], "rect-en");
+    check(&["de"], "rect-default");
+}
+
+#[test]
+fn odd_dasharray() {
+    let tree = usvg::Tree::from_str(
+        "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>\
+             <path id='path1' d='M 10 10 H 190' stroke='black' stroke-dasharray='5 3 2'/>\
+             <path id='path2' d='M 10 20 H 190' stroke='black' stroke-dasharray='0 0 0'/>\
+         </svg>",
+        &usvg::Options::default(),
+    ).unwrap();
+
+    let dasharray = |id: &str| {
+        let node = tree.node_by_id(id).unwrap();
+        let dasharray = match *node.borrow() {
+            usvg::NodeKind::Path(ref path) => path.stroke.as_ref().unwrap().dasharray.clone(),
+            _ => unreachable!(),
+        };
+        dasharray
+    };
+
+    assert_eq!(dasharray("path1"), Some(vec![5.0, 3.0, 2.0, 5.0, 3.0, 2.0]));
+    assert_eq!(dasharray("path2"), None);
+}
+
+#[test]
+fn css_class_selector() {
+    let tree = usvg::Tree::from_str(
+        "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>\
+             <style>.cls { fill: red }</style>\
+             <rect id='rect1' class='cls' width='20' height='20' fill='green'/>\
+         </svg>",
+        &usvg::Options::default(),
+    ).unwrap();
+
+    // A stylesheet has a higher priority than presentation attributes.
+    let node = tree.node_by_id("rect1").unwrap();
+    match *node.borrow() {
+        usvg::NodeKind::Path(ref path) => {
+            match path.fill.as_ref().unwrap().paint {
+                usvg::Paint::Color(c) => assert_eq!(c, usvg::Color::new(255, 0, 0)),
+                _ => unreachable!(),
+            }
+        }
+        _ => unreachable!(),
+    };
+}
+
+#[test]
+fn fallback_paint() {
+    let svg = "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>\
+                   <rect id='rect1' width='20' height='20' fill='url(#missing)'/>\
+                   <rect id='rect2' width='20' height='20' fill='url(#missing) green'/>\
+               </svg>";
+
+    let opt = usvg::Options {
+        fallback_paint: Some(usvg::Color::new(255, 0, 255)),
+        ..usvg::Options::default()
+    };
+
+    let fill_color = |tree: &usvg::Tree, id: &str| {
+        let node = tree.node_by_id(id)?;
+        let color = match *node.borrow() {
+            usvg::NodeKind::Path(ref path) => match path.fill.as_ref()?.paint {
+                usvg::Paint::Color(c) => Some(c),
+                _ => None,
+            },
+            _ => None,
+        };
+        color
+    };
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    assert_eq!(fill_color(&tree, "rect1"), None);
+
+    let tree = usvg::Tree::from_str(svg, &opt).unwrap();
+    assert_eq!(fill_color(&tree, "rect1"), Some(usvg::Color::new(255, 0, 255)));
+    assert_eq!(fill_color(&tree, "rect2"), Some(usvg::Color::new(0, 128, 0)));
+}
+
+#[test]
+fn root_svg_opacity() {
+    let tree = usvg::Tree::from_str(
+        "<svg viewBox='0 0 200 200' opacity='0.5' xmlns='http://www.w3.org/2000/svg'>\
+             <rect width='20' height='20'/>\
+             <rect x='40' width='20' height='20'/>\
+         </svg>",
+        &usvg::Options::default(),
+    ).unwrap();
+
+    // The whole content should be wrapped in a semi-transparent group.
+    let children: Vec<_> = tree.root().children().skip(1).collect();
+    assert_eq!(children.len(), 1);
+    match *children[0].borrow() {
+        usvg::NodeKind::Group(ref g) => assert_eq!(g.opacity.value(), 0.5),
+        _ => unreachable!(),
+    };
+    assert_eq!(children[0].children().count(), 2);
+}
+
+#[test]
+fn from_reader() {
+    use std::io::Write;
+
+    let svg = "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>\
+                   <rect id='rect1' width='20' height='20'/>\
+               </svg>";
+
+    let tree = usvg::Tree::from_reader(svg.as_bytes(), &usvg::Options::default()).unwrap();
+    assert!(tree.node_by_id("rect1").is_some());
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(svg.as_bytes()).unwrap();
+    let data = encoder.finish().unwrap();
+
+    let tree = usvg::Tree::from_reader(data.as_slice(), &usvg::Options::default()).unwrap();
+    assert!(tree.node_by_id("rect1").is_some());
+
+    assert!(usvg::Tree::from_reader(&[0x1f, 0x8b, 0x00][..], &usvg::Options::default()).is_err());
+}